@@ -0,0 +1,55 @@
+use macroquad::prelude as mq;
+use macroquad::rand::ChooseRandom;
+
+use crate::{colors, consts};
+
+// extra traits stacked on top of an enemy type, enemies with any are elites
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Affix {
+    Shielded,   // blocks the first few hits
+    Swift,      // moves faster
+    Splitting,  // spawns pawns on death
+    Vampiric,   // heals when it hits the player
+    Reflective, // bounces a bullet back
+}
+
+impl Affix {
+    pub fn roll(wave: i32, can_be_vampiric: bool) -> Vec<Affix> {
+        if mq::rand::gen_range(0.0, 1.0) >= consts::ENEMY_ELITE_CHANCE(wave) {
+            return Vec::new();
+        }
+
+        let mut affixes = vec![
+            Affix::Shielded,
+            Affix::Swift,
+            Affix::Splitting,
+            Affix::Reflective,
+        ];
+        if can_be_vampiric {
+            affixes.push(Affix::Vampiric);
+        }
+        affixes.shuffle();
+        affixes.truncate(consts::ENEMY_ELITE_AFFIX_COUNT(wave));
+        affixes
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Affix::Shielded => "Shielded",
+            Affix::Swift => "Swift",
+            Affix::Splitting => "Splitting",
+            Affix::Vampiric => "Vampiric",
+            Affix::Reflective => "Reflective",
+        }
+    }
+
+    pub fn color(&self) -> mq::Color {
+        match self {
+            Affix::Shielded => colors::NORD9,
+            Affix::Swift => colors::NORD13,
+            Affix::Splitting => colors::NORD15,
+            Affix::Vampiric => colors::NORD11,
+            Affix::Reflective => colors::NORD7,
+        }
+    }
+}
@@ -1,27 +1,33 @@
 use macroquad::prelude as mq;
 
-use crate::{camera, colors, consts, deck, hitbox, powerup};
+use crate::{camera, colors, consts, deck, hitbox, stats, status_effect};
 
+#[derive(Clone, Copy)]
 pub enum BulletDamage {
     Standard(f32),
     Card(deck::Card),
+    Wild(deck::Card, f32), // (card copied by a joker, damage modifier)
 }
 
 pub struct BulletHitResult {
     pub damage: f32,
-    pub stun_time: f32,
+    pub status_effects: Vec<status_effect::StatusEffect>,
     pub heal_amount: f32,
+    pub kill_xp: i32,
 }
 
 pub struct Bullet {
-    start_pos: mq::Vec2,     // in tiles
     pos: mq::Vec2,           // in tiles
     direction: f32,          // in radians
     speed: f32,              // in tiles per second
     distance_to_travel: f32, // in tiles
+    distance_traveled: f32,  // in tiles
     pub bullet_damage: BulletDamage,
     hp: i32,
     hit_enemies: Vec<usize>,
+    size: f32,           // in scale
+    homing: Option<f32>, // turn rate in radians per second
+    damage_mod: f32,
 }
 
 impl Bullet {
@@ -34,17 +40,35 @@ impl Bullet {
         hp: i32,
     ) -> Self {
         Self {
-            start_pos,
             pos: start_pos,
             direction,
             speed,
             distance_to_travel,
+            distance_traveled: 0.0,
             bullet_damage,
             hp,
             hit_enemies: Vec::with_capacity(hp as usize - 1),
+            size: consts::BULLET_SIZE,
+            homing: None,
+            damage_mod: 1.0,
         }
     }
 
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_homing(mut self, homing: Option<f32>) -> Self {
+        self.homing = homing;
+        self
+    }
+
+    pub fn with_damage_mod(mut self, damage_mod: f32) -> Self {
+        self.damage_mod = damage_mod;
+        self
+    }
+
     pub fn should_keep(&self) -> bool {
         self.hp > 0
     }
@@ -64,26 +88,86 @@ impl Bullet {
         self.hp = 0;
     }
 
-    pub fn hit_result(&mut self, powerups: &powerup::Powerups) -> BulletHitResult {
-        match self.bullet_damage {
-            BulletDamage::Standard(damage) => BulletHitResult {
-                damage,
-                stun_time: 0.0,
-                heal_amount: 0.0,
-            },
-            BulletDamage::Card(card) => BulletHitResult {
-                damage: card.damage(Some(powerups)),
-                stun_time: card.stun_time(powerups),
-                heal_amount: card.heal_amount(powerups),
-            },
+    // a copy going back the way it came, now hurting the player
+    pub fn reflected(&self, pos: mq::Vec2, damage: f32) -> Bullet {
+        Bullet::new(
+            pos,
+            self.direction + std::f32::consts::PI,
+            consts::ENEMY_RANGED_BULLET_SPEED,
+            consts::ENEMY_RANGED_BULLET_RANGE,
+            BulletDamage::Standard(damage),
+            1,
+        )
+    }
+
+    pub fn hit_result(&mut self, stats: &stats::Stats) -> BulletHitResult {
+        let (card, damage_mod) = match self.bullet_damage {
+            BulletDamage::Standard(damage) => {
+                return BulletHitResult {
+                    damage,
+                    status_effects: Vec::new(),
+                    heal_amount: 0.0,
+                    kill_xp: 0,
+                }
+            }
+            BulletDamage::Card(card) => (card, 1.0),
+            BulletDamage::Wild(card, damage_mod) => (card, damage_mod),
+        };
+
+        let mut status_effects = stats.on_hit_status_effects(card.suit);
+        let knockback_speed = card.knockback_speed();
+        if knockback_speed > 0.0 {
+            let direction_vec = mq::Vec2::new(self.direction.cos(), self.direction.sin());
+            status_effects.push(status_effect::StatusEffect::Knockback(
+                direction_vec * knockback_speed,
+            ));
+        }
+
+        let crit_mod = if mq::rand::gen_range(0.0, 1.0) < stats.crit_chance(card.suit) {
+            consts::PLAYER_CRIT_DAMAGE_MOD
+        } else {
+            1.0
+        };
+
+        BulletHitResult {
+            damage: card.damage() * damage_mod * self.damage_mod * stats.damage() * crit_mod,
+            status_effects,
+            heal_amount: stats.heal_amount(card.suit),
+            kill_xp: card.kill_xp(),
+        }
+    }
+
+    pub fn update_homing(&mut self, targets: impl Iterator<Item = mq::Vec2>, delta: f32) {
+        let Some(turn_rate) = self.homing else {
+            return;
+        };
+
+        let target = targets
+            .filter(|target| target.distance(self.pos) < consts::BULLET_HOMING_RANGE)
+            .min_by(|a, b| {
+                a.distance_squared(self.pos)
+                    .partial_cmp(&b.distance_squared(self.pos))
+                    .unwrap()
+            });
+
+        if let Some(target) = target {
+            let vec_to_target = target - self.pos;
+            let target_direction = vec_to_target.y.atan2(vec_to_target.x);
+
+            // shortest signed angle between the two directions
+            let dif = (target_direction - self.direction + std::f32::consts::PI)
+                .rem_euclid(2.0 * std::f32::consts::PI)
+                - std::f32::consts::PI;
+            let max_turn = turn_rate * delta;
+            self.direction += dif.clamp(-max_turn, max_turn);
         }
     }
 
     pub fn update(&mut self, delta: f32) {
         let direction_vec = mq::Vec2::new(self.direction.cos(), self.direction.sin());
         self.pos += direction_vec * self.speed * delta;
-        let distance_traveled = self.pos.distance(self.start_pos);
-        if distance_traveled >= self.distance_to_travel {
+        self.distance_traveled += self.speed * delta;
+        if self.distance_traveled >= self.distance_to_travel {
             self.remove();
         }
     }
@@ -94,10 +178,10 @@ impl Bullet {
         mq::draw_circle(
             draw_pos.x,
             draw_pos.y,
-            scale * consts::BULLET_SIZE,
+            scale * self.size,
             match self.bullet_damage {
                 BulletDamage::Standard(_) => colors::NORD8,
-                BulletDamage::Card(card) => {
+                BulletDamage::Card(card) | BulletDamage::Wild(card, _) => {
                     if card.is_red() {
                         colors::NORD11
                     } else {
@@ -109,7 +193,7 @@ impl Bullet {
         mq::draw_circle_lines(
             draw_pos.x,
             draw_pos.y,
-            scale * consts::BULLET_SIZE,
+            scale * self.size,
             scale * consts::BULLET_OUTLINE,
             colors::NORD4,
         )
@@ -122,6 +206,6 @@ impl hitbox::Circle for Bullet {
     }
 
     fn radius(&self) -> f32 {
-        consts::BULLET_SIZE * consts::TILES_PER_SCALE as f32
+        self.size * consts::TILES_PER_SCALE as f32
     }
 }
@@ -0,0 +1,74 @@
+use macroquad::prelude as mq;
+
+use crate::{bullet, consts};
+
+// volleys of enemy bullets, the volley number lets a pattern change between shots
+#[derive(Clone, Copy)]
+pub enum BulletPattern {
+    Spiral(u32, f32),     // arms, turn per volley in radians
+    Ring(u32),            // bullet count, every other ring fills the last one's gaps
+    AimedBurst(u32, f32), // bullet count, total spread
+    Wall(u32, u32),       // bullet count, gap size in bullets
+}
+
+impl BulletPattern {
+    pub fn fire(&self, pos: mq::Vec2, aim: f32, volley: u32, damage: f32) -> Vec<bullet::Bullet> {
+        let bullet = |pos: mq::Vec2, angle: f32, speed: f32| {
+            bullet::Bullet::new(
+                pos,
+                angle,
+                speed,
+                consts::ENEMY_RANGED_BULLET_RANGE,
+                bullet::BulletDamage::Standard(damage),
+                1,
+            )
+        };
+        let speed = consts::ENEMY_RANGED_BULLET_SPEED;
+
+        match *self {
+            BulletPattern::Spiral(arms, turn) => (0..arms)
+                .map(|arm| {
+                    let angle = volley as f32 * turn
+                        + 2.0 * std::f32::consts::PI * arm as f32 / arms as f32;
+                    bullet(pos, angle, speed)
+                })
+                .collect(),
+            BulletPattern::Ring(count) => {
+                let step = 2.0 * std::f32::consts::PI / count as f32;
+                let offset = if volley.is_multiple_of(2) {
+                    0.0
+                } else {
+                    step / 2.0
+                };
+                (0..count)
+                    .map(|i| bullet(pos, aim + offset + step * i as f32, speed))
+                    .collect()
+            }
+            BulletPattern::AimedBurst(count, spread) => (0..count)
+                .map(|i| {
+                    let ratio = if count > 1 {
+                        i as f32 / (count - 1) as f32
+                    } else {
+                        0.5
+                    };
+                    // the middle bullets lead, making a V pointed at the player
+                    let edge = (ratio - 0.5).abs() * 2.0;
+                    let speed = speed * (1.0 - consts::BULLET_PATTERN_BURST_SPEED_FALLOFF * edge);
+                    bullet(pos, aim - spread / 2.0 + spread * ratio, speed)
+                })
+                .collect(),
+            BulletPattern::Wall(count, gap) => {
+                let sideways = mq::Vec2::from_angle(aim).perp();
+                let gap_start = mq::rand::gen_range(0, count.saturating_sub(gap) + 1);
+                (0..count)
+                    .filter(|i| !(gap_start..gap_start + gap).contains(i))
+                    .map(|i| {
+                        let offset = (i as f32 - (count - 1) as f32 / 2.0)
+                            * consts::BULLET_PATTERN_WALL_SPACING;
+                        bullet(pos + sideways * offset, aim, speed)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
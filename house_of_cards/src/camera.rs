@@ -26,4 +26,16 @@ impl Camera {
 
         util::Moved(old_pos != self.pos)
     }
+
+    // the visible area in tiles
+    pub fn view_rect(&self, scale: f32) -> mq::Rect {
+        let tile_size = scale / consts::TILES_PER_SCALE as f32;
+        let size = mq::Vec2::new(mq::screen_width(), mq::screen_height()) / tile_size;
+        mq::Rect::new(
+            self.pos.x - size.x / 2.0,
+            self.pos.y - size.y / 2.0,
+            size.x,
+            size.y,
+        )
+    }
 }
@@ -45,6 +45,7 @@ pub const NORD13: mq::Color = mq::Color::new(0.922, 0.796, 0.545, 1.0);
 pub const NORD14: mq::Color = mq::Color::new(0.639, 0.745, 0.549, 1.0);
 pub const NORD15: mq::Color = mq::Color::new(0.706, 0.557, 0.678, 1.0);
 
+pub const NORD0_ALPHA: mq::Color = mq::Color::new(0.180, 0.204, 0.251, 0.9);
 pub const NORD3_ALPHA: mq::Color = mq::Color::new(0.298, 0.337, 0.416, 0.9);
 pub const NORD6_ALPHA: mq::Color = mq::Color::new(0.925, 0.937, 0.969, 0.9);
 pub const NORD11_ALPHA: mq::Color = mq::Color::new(0.749, 0.380, 0.416, 0.9);
@@ -53,8 +54,10 @@ pub const NORD14_ALPHA: mq::Color = mq::Color::new(0.639, 0.745, 0.549, 0.9);
 pub const NORD0_BIG_ALPHA: mq::Color = mq::Color::new(0.180, 0.204, 0.251, 0.5);
 pub const NORD4_BIG_ALPHA: mq::Color = mq::Color::new(0.847, 0.871, 0.914, 0.5);
 pub const NORD6_BIG_ALPHA: mq::Color = mq::Color::new(0.925, 0.937, 0.969, 0.5);
+pub const NORD8_BIG_ALPHA: mq::Color = mq::Color::new(0.533, 0.753, 0.816, 0.5);
 pub const NORD11_BIG_ALPHA: mq::Color = mq::Color::new(0.749, 0.380, 0.416, 0.5);
 pub const NORD14_BIG_ALPHA: mq::Color = mq::Color::new(0.639, 0.745, 0.549, 0.5);
+pub const NORD15_BIG_ALPHA: mq::Color = mq::Color::new(0.706, 0.557, 0.678, 0.5);
 
 pub const SURFACE2: mq::Color = mq::color_u8!(98, 104, 128, 255);
 pub const SURFACE1: mq::Color = mq::color_u8!(81, 87, 109, 255);
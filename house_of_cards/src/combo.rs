@@ -0,0 +1,241 @@
+use crate::{consts, deck};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Combo {
+    None,
+    Pair,
+    TwoPair,
+    Flush,
+    Straight,
+    ThreeOfAKind,
+    StraightFlush,
+}
+
+impl Combo {
+    pub fn evaluate(cards: &[deck::Card]) -> Self {
+        // jokers are wild: they count as any rank or suit
+        let ranked = cards
+            .iter()
+            .filter(|card| card.suit != deck::Suit::Joker)
+            .collect::<Vec<_>>();
+        let jokers = cards.len() - ranked.len();
+
+        // index by card value (1-13)
+        let mut counts = [0; 14];
+        for card in ranked.iter() {
+            counts[card.value as usize] += 1;
+        }
+        let max_count = counts.iter().copied().max().unwrap_or(0);
+
+        // each joker pairs up a single card, leftover jokers pair up with each other
+        let natural_pairs = counts.iter().filter(|count| **count >= 2).count();
+        let singles = counts.iter().filter(|count| **count == 1).count();
+        let joker_pairs = jokers.min(singles) + jokers.saturating_sub(singles) / 2;
+        let pair_count = natural_pairs + joker_pairs;
+
+        let full_hand = cards.len() >= consts::COMBO_MIN_CARDS;
+
+        let is_flush = full_hand && ranked.iter().all(|card| card.suit == ranked[0].suit);
+
+        let is_straight = full_hand && max_count <= 1 && {
+            let values = ranked.iter().map(|card| card.value as i32);
+            // ace can be low (1) or high (14)
+            let ace_high = values
+                .clone()
+                .map(|value| if value == 1 { 14 } else { value });
+
+            // jokers fill in any gaps
+            let is_run = |values: Vec<i32>| {
+                let min = values.iter().min().unwrap_or(&0);
+                let max = values.iter().max().unwrap_or(&0);
+                values.is_empty() || ((max - min) as usize) < cards.len()
+            };
+            is_run(values.collect()) || is_run(ace_high.collect())
+        };
+
+        if is_straight && is_flush {
+            Combo::StraightFlush
+        } else if max_count + jokers >= 3 {
+            Combo::ThreeOfAKind
+        } else if is_straight {
+            Combo::Straight
+        } else if is_flush {
+            Combo::Flush
+        } else if pair_count >= 2 {
+            Combo::TwoPair
+        } else if pair_count == 1 {
+            Combo::Pair
+        } else {
+            Combo::None
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Combo::None => "",
+            Combo::Pair => "Pair",
+            Combo::TwoPair => "Two Pair",
+            Combo::Flush => "Flush",
+            Combo::Straight => "Straight",
+            Combo::ThreeOfAKind => "Three of a Kind",
+            Combo::StraightFlush => "Straight Flush",
+        }
+    }
+
+    // built from the modifiers so the text always matches them
+    pub fn bonus_text(&self) -> String {
+        let damage_mod = self.damage_mod();
+        let fire_rate_mod = self.fire_rate_mod();
+        let extra_pierce = self.extra_pierce();
+
+        let mut parts = Vec::new();
+        if damage_mod != 1.0 && damage_mod == fire_rate_mod {
+            parts.push(format!("x{} damage/rate", damage_mod));
+        } else {
+            if damage_mod != 1.0 {
+                parts.push(format!("x{} damage", damage_mod));
+            }
+            if fire_rate_mod != 1.0 {
+                parts.push(format!("x{} fire rate", fire_rate_mod));
+            }
+        }
+        if extra_pierce > 0 {
+            parts.push(format!("+{} pierce", extra_pierce));
+        }
+        parts.join(", ")
+    }
+
+    pub fn fire_rate_mod(&self) -> f32 {
+        match self {
+            Combo::Straight => consts::COMBO_STRAIGHT_FIRE_RATE_MOD,
+            Combo::StraightFlush => consts::COMBO_STRAIGHT_FLUSH_MOD,
+            _ => 1.0,
+        }
+    }
+
+    pub fn extra_pierce(&self) -> i32 {
+        match self {
+            Combo::Flush => consts::COMBO_FLUSH_PIERCE,
+            Combo::StraightFlush => consts::COMBO_STRAIGHT_FLUSH_PIERCE,
+            _ => 0,
+        }
+    }
+
+    pub fn damage_mod(&self) -> f32 {
+        match self {
+            Combo::Pair => consts::COMBO_PAIR_DAMAGE_MOD,
+            Combo::TwoPair => consts::COMBO_TWO_PAIR_DAMAGE_MOD,
+            Combo::ThreeOfAKind => consts::COMBO_THREE_OF_A_KIND_DAMAGE_MOD,
+            Combo::StraightFlush => consts::COMBO_STRAIGHT_FLUSH_MOD,
+            _ => 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deck::{Card, Suit};
+
+    fn evaluate(cards: &[(Suit, u8)]) -> Combo {
+        let cards = cards
+            .iter()
+            .map(|(suit, value)| Card::new(*suit, *value))
+            .collect::<Vec<_>>();
+        Combo::evaluate(&cards)
+    }
+
+    #[test]
+    fn pairs_and_three_of_a_kind() {
+        assert!(evaluate(&[(Suit::Spades, 5), (Suit::Hearts, 5)]) == Combo::Pair);
+        assert!(
+            evaluate(&[
+                (Suit::Spades, 5),
+                (Suit::Hearts, 5),
+                (Suit::Clubs, 9),
+                (Suit::Diamonds, 9),
+            ]) == Combo::TwoPair
+        );
+        assert!(
+            evaluate(&[(Suit::Spades, 5), (Suit::Hearts, 5), (Suit::Clubs, 5)])
+                == Combo::ThreeOfAKind
+        );
+        assert!(evaluate(&[(Suit::Spades, 5), (Suit::Hearts, 9)]) == Combo::None);
+        assert!(evaluate(&[]) == Combo::None);
+    }
+
+    #[test]
+    fn jokers_are_wild() {
+        assert!(evaluate(&[(Suit::Spades, 5), (Suit::Joker, 0)]) == Combo::Pair);
+        assert!(evaluate(&[(Suit::Joker, 0), (Suit::Joker, 1)]) == Combo::Pair);
+        assert!(
+            evaluate(&[(Suit::Spades, 5), (Suit::Hearts, 5), (Suit::Joker, 0)])
+                == Combo::ThreeOfAKind
+        );
+        // both jokers join the same card rather than making two pairs
+        assert!(
+            evaluate(&[
+                (Suit::Spades, 5),
+                (Suit::Hearts, 9),
+                (Suit::Joker, 0),
+                (Suit::Joker, 1),
+            ]) == Combo::ThreeOfAKind
+        );
+        // fills the gap in a straight
+        assert!(
+            evaluate(&[(Suit::Spades, 4), (Suit::Hearts, 6), (Suit::Joker, 0)]) == Combo::Straight
+        );
+        // counts as the missing suit
+        assert!(evaluate(&[(Suit::Clubs, 2), (Suit::Clubs, 9), (Suit::Joker, 0)]) == Combo::Flush);
+        assert!(
+            evaluate(&[(Suit::Clubs, 4), (Suit::Clubs, 6), (Suit::Joker, 0)])
+                == Combo::StraightFlush
+        );
+    }
+
+    #[test]
+    fn flushes_and_straights_need_three_cards() {
+        // two cards of a suit are just two cards
+        assert!(evaluate(&[(Suit::Hearts, 2), (Suit::Hearts, 9)]) == Combo::None);
+        assert!(evaluate(&[(Suit::Hearts, 2), (Suit::Spades, 3)]) == Combo::None);
+        assert!(evaluate(&[(Suit::Hearts, 2), (Suit::Hearts, 3)]) == Combo::None);
+
+        assert!(
+            evaluate(&[(Suit::Hearts, 2), (Suit::Hearts, 9), (Suit::Hearts, 12)]) == Combo::Flush
+        );
+        assert!(
+            evaluate(&[(Suit::Hearts, 2), (Suit::Spades, 3), (Suit::Clubs, 4)]) == Combo::Straight
+        );
+        assert!(
+            evaluate(&[(Suit::Hearts, 2), (Suit::Hearts, 3), (Suit::Hearts, 4)])
+                == Combo::StraightFlush
+        );
+    }
+
+    #[test]
+    fn aces_are_low_or_high() {
+        assert!(
+            evaluate(&[(Suit::Hearts, 1), (Suit::Spades, 2), (Suit::Clubs, 3)]) == Combo::Straight
+        );
+        assert!(
+            evaluate(&[(Suit::Hearts, 12), (Suit::Spades, 13), (Suit::Clubs, 1)])
+                == Combo::Straight
+        );
+        // but straights don't wrap around
+        assert!(
+            evaluate(&[(Suit::Hearts, 13), (Suit::Spades, 1), (Suit::Clubs, 2)]) == Combo::None
+        );
+    }
+
+    #[test]
+    fn bonus_text_matches_the_modifiers() {
+        assert_eq!(Combo::None.bonus_text(), "");
+        assert_eq!(Combo::Pair.bonus_text(), "x1.25 damage");
+        assert_eq!(Combo::Flush.bonus_text(), "+1 pierce");
+        assert_eq!(Combo::Straight.bonus_text(), "x1.3 fire rate");
+        assert_eq!(
+            Combo::StraightFlush.bonus_text(),
+            "x1.5 damage/rate, +2 pierce"
+        );
+    }
+}
@@ -18,6 +18,8 @@ pub const SUIT_ACE_IDX_X: usize = 13;
 pub const FONT_PATH: &str = "resources/Assistant-SemiBold.ttf";
 pub const MUSIC_PATH: &str = "resources/INTERSTELLAR-SHORT.wav";
 
+pub const WAVES_PATH: &str = "resources/waves.json"; // optional, see README
+
 pub const BACKGROUND_COLOR: mq::Color = crate::colors::CRUST;
 pub const MOUSE_COLOR: mq::Color = crate::colors::NORD6;
 pub const BACKGROUND_COLORS: [mq::Color; 6] = colors::SURFACE_COLORS;
@@ -32,9 +34,17 @@ pub const JOYSTICK_HEIGHT: f32 = 2.0 / 3.0; // height
 pub const PAUSE_BUTTON_WIDTH: f32 = 0.2;
 pub const PAUSE_BUTTON_HEIGHT: f32 = 0.3;
 
+pub const DASH_BUTTON_RADIUS: f32 = 0.06; // scale
+pub const DASH_BUTTON_MARGIN: f32 = 0.04; // scale
+
 pub const PLAYER_SPEED: f32 = 1.2; // tiles per second
 pub const PLAYER_SIZE: f32 = 0.03; // scale
 pub const PLAYER_MAX_HEALTH: f32 = 10.0;
+pub const PLAYER_CRIT_CHANCE: f32 = 0.0; // percent
+pub const PLAYER_CRIT_DAMAGE_MOD: f32 = 2.0;
+pub const PLAYER_ARMOR: f32 = 0.0; // percent of damage blocked
+pub const PLAYER_MAX_ARMOR: f32 = 0.75; // percent
+pub const PLAYER_REGEN: f32 = 0.0; // hp per second
 
 pub const PLAYER_HP_BAR_WIDTH: f32 = 0.5; // scale
 pub const PLAYER_HP_BAR_HEIGHT: f32 = 0.025; // scale
@@ -43,6 +53,19 @@ pub const PLAYER_HP_BAR_THICKNESS: f32 = 0.0075; // scale
 pub const PLAYER_XP_BAR_OFFSET: f32 = 0.01; // scale
 pub const PLAYER_BARS_FONT_RATIO: f32 = 0.85; // percent
 
+pub const PLAYER_HIT_IFRAME_TIME: f32 = 0.6; // seconds
+pub const PLAYER_HIT_FLASH_PERIOD: f32 = 0.1; // seconds
+pub const PLAYER_HIT_FLASH_ALPHA: f32 = 0.3;
+pub const PLAYER_HIT_KNOCKBACK_SPEED: f32 = 4.0; // tiles per second
+pub const PLAYER_HIT_KNOCKBACK_DECAY: f32 = 10.0; // per second, exponential
+
+pub const PLAYER_DASH_SPEED: f32 = 8.0; // tiles per second
+pub const PLAYER_DASH_TIME: f32 = 0.15; // seconds
+pub const PLAYER_DASH_IFRAME_TIME: f32 = 0.3; // seconds
+pub const PLAYER_DASH_COOLDOWN: f32 = 2.0; // seconds
+pub const PLAYER_DASH_INDICATOR_OFFSET: f32 = 0.02; // scale
+pub const PLAYER_DASH_INDICATOR_THICKNESS: f32 = 0.004; // scale
+
 pub const FPS_TEXT_UPDATE_PERIOD: f32 = 1.0 / 10.0; // seconds
 
 pub const CAMERA_FOLLOW_SPEED: f32 = 0.95;
@@ -70,14 +93,37 @@ pub const SMALL_FONT_BUTTON_PADDING: f32 = 0.8; // text height
 
 pub const CONTROLS_LARGE_TEXT_SPACING: f32 = 0.2; // height
 pub const CONTROLS_SMALL_FONT_SPACING: f32 = 0.04; // scale
+pub const CONTROLS_GAME_MODE_COLOR: mq::Color = colors::NORD13;
 
 pub const DAMAGE_FONT_SIZE: f32 = 0.025;
 pub const DAMAGE_NUMBER_TIME: f32 = 0.2; // seconds
 pub const DAMAGE_NUMBER_RAND_POS: f32 = 0.2;
 
-pub const HEARTS_HEAL_CHANCE: f32 = 0.02;
-pub const CLUBS_STUN_TIME: f32 = 0.1; // seconds
-pub const SPADES_DAMAGE_CHANCE: f32 = 0.33;
+pub const DIAMONDS_PIERCE: f32 = 1.0; // enemies, per powerup
+pub const HEARTS_HEAL_CHANCE: f32 = 0.02; // per powerup
+pub const HEARTS_HEAL_AMOUNT: f32 = 1.0;
+pub const CLUBS_STUN_TIME: f32 = 0.1; // seconds, per powerup
+pub const SPADES_CRIT_CHANCE: f32 = 0.33; // per powerup
+pub const DIAMONDS_BURN_TIME: f32 = 2.0; // seconds
+pub const DIAMONDS_BURN_DAMAGE: f32 = 2.0; // per second, per powerup
+pub const HEARTS_VULNERABLE_TIME: f32 = 2.0; // seconds
+pub const HEARTS_VULNERABLE_DAMAGE: f32 = 0.25; // extra damage taken, per powerup
+pub const CLUBS_FREEZE_CHANCE: f32 = 0.1; // per powerup
+pub const CLUBS_FREEZE_TIME: f32 = 1.0; // seconds
+pub const CLUBS_SLOW_TIME: f32 = 1.5; // seconds
+pub const CLUBS_SLOW_SPEED_MOD: f32 = 0.8; // per powerup
+pub const SPADES_POISON_TIME: f32 = 3.0; // seconds
+pub const SPADES_POISON_STACKS: f32 = 1.0; // per powerup
+pub const HEARTS_REGEN: f32 = 0.1; // hp per second, per powerup
+pub const CLUBS_ARMOR: f32 = 0.1; // percent, per powerup
+
+pub const STATUS_POISON_DAMAGE_PER_STACK: f32 = 0.5; // per second
+pub const STATUS_POISON_MAX_STACKS: u32 = 20;
+pub const STATUS_KNOCKBACK_DECAY: f32 = 8.0; // per second
+pub const STATUS_KNOCKBACK_MIN_SPEED: f32 = 0.05; // tiles per second
+pub const STATUS_PIP_SIZE: f32 = 0.006; // scale
+
+pub const KING_KNOCKBACK_SPEED: f32 = 4.0; // tiles per second
 
 pub const POWERUP_PICK_FONT_LARGE: f32 = 0.043; // scale
 pub const POWERUP_PICK_FONT_SMALL: f32 = 0.03; // scale
@@ -89,7 +135,9 @@ pub const POWERUP_DIPLAY_Y_OFFSET: f32 = 0.027;
 pub const POWERUP_DISPLAY_MAX_HEIGHT: usize = 8;
 pub const POWERUP_OUTLINE_THICKNESS: f32 = 0.0075;
 
-pub const HAND_CARD_COUNT: usize = 3;
+pub const HAND_START_SIZE: usize = 3;
+pub const HAND_MIN_SIZE: usize = 1;
+pub const HAND_MAX_SIZE: usize = 6;
 pub const HAND_BOTTOM_PADDING: f32 = 0.1;
 pub const HAND_TOTAL_MAX_WIDTH: f32 = 0.6;
 pub const HAND_TOTAL_MAX_HEIGHT: f32 = 0.2;
@@ -97,20 +145,136 @@ pub const HAND_SPACING: f32 = 0.2; // relative to card width
 pub const HAND_NUMBER_ICON_FONT_SIZE: f32 = 0.025; // scale
 pub const HAND_OUTLINE_THICKNESS: f32 = 0.0025;
 pub const SLOT_MAX_START_VALUE: u8 = 4;
+pub const HAND_COMBO_FONT_SIZE: f32 = 0.03; // scale
+pub const HAND_COMBO_SUB_FONT_RATIO: f32 = 0.75; // percent
+pub const HAND_AMMO_FONT_SIZE: f32 = 0.02; // scale
+
+pub const AMMO_SHOTS_PER_VALUE: u32 = 3; // number cards
+pub const AMMO_FACE_SHOTS: u32 = 12;
+pub const AMMO_ACE_SHOTS: u32 = 3;
+pub const AMMO_JOKER_SHOTS: u32 = 15;
+
+pub const COMBO_MIN_CARDS: usize = 3; // for flushes and straights
+pub const COMBO_PAIR_DAMAGE_MOD: f32 = 1.25;
+pub const COMBO_TWO_PAIR_DAMAGE_MOD: f32 = 1.5;
+pub const COMBO_FLUSH_PIERCE: i32 = 1;
+pub const COMBO_STRAIGHT_FIRE_RATE_MOD: f32 = 1.3;
+pub const COMBO_THREE_OF_A_KIND_DAMAGE_MOD: f32 = 2.0;
+pub const COMBO_STRAIGHT_FLUSH_MOD: f32 = 1.5; // damage and fire rate
+pub const COMBO_STRAIGHT_FLUSH_PIERCE: i32 = 2;
 
 pub const CARD_CHOICE_MAX_WIDTH: f32 = 0.9;
 pub const CARD_CHOICE_MAX_PERCENT_HEIGHT: f32 = 0.9;
 pub const CARD_CHOICE_OUTLINE_THICKNESS: f32 = 0.005;
 pub const CARD_CHOICE_SPACING: f32 = 0.1; // relative to card width
 pub const CARD_CHOICE_FONT_SIZE: f32 = 0.04; // scale
-
-pub const JOKER_WEAPON: weapon::Weapon = weapon::Weapon::new(1.0 / 8.0, 15.0, 0.8, 0.6, 5.0);
-pub const ACE_WEAPON: weapon::Weapon = weapon::Weapon::new(1.0 / 20.0, 20.0, 0.8, 0.7, 10.0);
-pub const FACE_WEAPON: weapon::Weapon = weapon::Weapon::new(1.0 / 0.5, 15.0, 1.0, 0.75, 7.5);
-pub const ELSE_WEAPON: weapon::Weapon = weapon::Weapon::new(1.0 / 0.2, 15.0, 1.0, 0.75, 7.5);
+pub const CARD_CHOICE_ENCHANTMENT_FONT_SIZE: f32 = 0.02; // scale
+
+pub const ENCHANTMENT_OVERLAY_ALPHA: f32 = 0.2;
+pub const ENCHANTMENT_OUTLINE_THICKNESS: f32 = 0.004; // scale
+pub const ENCHANTMENT_FOIL_DAMAGE_MOD: f32 = 1.5;
+pub const ENCHANTMENT_GILDED_XP: i32 = 1; // per kill
+pub const ENCHANTMENT_GLASS_DAMAGE_MOD: f32 = 3.0;
+pub const ENCHANTMENT_GLASS_SHOTS: u32 = 30;
+pub const ENCHANTMENT_STEEL_PIERCE: i32 = 1;
+pub const ENCHANTMENT_STEEL_RANGE_MOD: f32 = 1.25;
+
+pub const FUSE_MAX_VALUE: u8 = 13; // king
+
+pub const DECK_MIN_SIZE: usize = 16; // cards
+pub const DECK_EDIT_LEVEL_PERIOD: i32 = 3; // levels
+pub const DECK_EDIT_REMOVE_MAX_VALUE: u8 = 5;
+
+pub const DECK_VIEWER_PADDING: f32 = 0.03; // scale
+pub const DECK_VIEWER_FONT_SIZE: f32 = 0.035; // scale
+pub const DECK_VIEWER_HEADER_RATIO: f32 = 1.5; // font size
+pub const DECK_VIEWER_COLUMNS: usize = 14;
+pub const DECK_VIEWER_SPACING: f32 = 0.1; // relative to card width
+
+pub const JOKER_WEAPON: weapon::Weapon = weapon::Weapon::new(
+    1.0 / 8.0,
+    15.0,
+    0.8,
+    0.6,
+    5.0,
+    weapon::BulletPattern::Single,
+);
+pub const ACE_WEAPON: weapon::Weapon = weapon::Weapon::new(
+    1.0 / 20.0,
+    20.0,
+    0.8,
+    0.7,
+    10.0,
+    weapon::BulletPattern::Single,
+);
+pub const KING_WEAPON: weapon::Weapon = weapon::Weapon::new(
+    1.0 / 0.7,
+    15.0,
+    1.0,
+    0.7,
+    6.0,
+    weapon::BulletPattern::Wide(3.0, 3),
+);
+pub const QUEEN_WEAPON: weapon::Weapon = weapon::Weapon::new(
+    1.0 / 0.8,
+    15.0,
+    1.0,
+    0.7,
+    3.5,
+    weapon::BulletPattern::Homing(4.0),
+);
+pub const JACK_WEAPON: weapon::Weapon = weapon::Weapon::new(
+    1.0 / 0.4,
+    18.0,
+    1.0,
+    0.75,
+    11.0,
+    weapon::BulletPattern::Single,
+);
+pub const NINE_WEAPON: weapon::Weapon = weapon::Weapon::new(
+    1.0 / 0.3,
+    12.0,
+    1.0,
+    0.75,
+    7.5,
+    weapon::BulletPattern::Spread(3, 0.3, 0.6),
+);
+pub const EIGHT_WEAPON: weapon::Weapon = weapon::Weapon::new(
+    1.0 / 0.25,
+    15.0,
+    1.0,
+    0.75,
+    7.5,
+    weapon::BulletPattern::Spread(2, 0.1, 0.9),
+);
+pub const SEVEN_WEAPON: weapon::Weapon = weapon::Weapon::new(
+    1.0 / 0.6,
+    6.0,
+    1.0,
+    0.75,
+    7.5,
+    weapon::BulletPattern::Spread(7, 0.7, 0.35),
+);
+pub const ELSE_WEAPON: weapon::Weapon = weapon::Weapon::new(
+    1.0 / 0.2,
+    15.0,
+    1.0,
+    0.75,
+    7.5,
+    weapon::BulletPattern::Single,
+);
+
+// jokers copy the strongest other card in the hand
+pub const JOKER_RED_DAMAGE_MOD: f32 = 3.0;
+pub const JOKER_RED_FIRE_RATE_MOD: f32 = 1.0;
+pub const JOKER_BLACK_DAMAGE_MOD: f32 = 1.5;
+pub const JOKER_BLACK_FIRE_RATE_MOD: f32 = 2.0;
 
 pub const BULLET_SIZE: f32 = 0.005; // scale
+pub const BULLET_PATTERN_BURST_SPEED_FALLOFF: f32 = 0.3; // percent slower at the edges
+pub const BULLET_PATTERN_WALL_SPACING: f32 = 0.4; // tiles
 pub const BULLET_OUTLINE: f32 = 0.0005; // scale
+pub const BULLET_HOMING_RANGE: f32 = 6.0; // tiles
 
 
 pub const CHESS_TEXTURE_PATH: &str = "resources/nord-chess-transparent.png";
@@ -118,12 +282,18 @@ pub const CHESS_PAWN_INDEX: usize = 0;
 pub const CHESS_BISHOP_INDEX: usize = 1;
 pub const CHESS_QUEEN_INDEX: usize = 2;
 pub const CHESS_KING_INDEX: usize = 3;
+pub const CHESS_ROOK_INDEX: usize = 4;
+pub const CHESS_KNIGHT_INDEX: usize = 5;
+pub const CHESS_ENEMY_KING_INDEX: usize = 6;
 // (x_offset, y_offset, width, height)
-pub const CHESS_TEXTURE_INFO: [(u32, u32, u32, u32); 4] = [
+pub const CHESS_TEXTURE_INFO: [(u32, u32, u32, u32); 7] = [
     (25,  112, 55, 80),
     (235, 107, 55, 85),
     (535, 107, 85, 85),
     (440, 10,  65, 85),
+    (340, 107, 55, 85),
+    (120, 107, 65, 85),
+    (440, 107, 65, 85),
 ];
 
 pub const ENEMY_SIZE: f32 = 0.025; // scale
@@ -149,26 +319,152 @@ pub const ENEMY_MELEE_CHARGE_THICKNESS: f32 = 0.005; // scale
 
 pub const ENEMY_SUPER_SIZE: f32 = 0.05;
 pub const ENEMY_SUPER_RANGE: f32 = 5.0;
-pub const ENEMY_WRAP_STRENGH: f32 = 1.975;
-pub const ENEMY_MAX_RANGE_MULT: f32 = 0.8;
 pub const ENEMY_SUPER_MIN_RANGE: f32 = 1.0;
 pub const ENEMY_SUPER_WAVE_START: i32 = 2;
-pub const ENEMY_SUPER_WAVE_FIRE_RATE: fn(i32) -> f32 =
-    |wave| (wave - ENEMY_SUPER_WAVE_START) as f32 / 6.0;
-pub const ENEMY_SUPER_SPREAD: f32 = 0.6; // radians
+// fire rate multiplier, capped so later waves stay dodgeable
+pub const ENEMY_SUPER_WAVE_FIRE_RATE_MOD: fn(i32) -> f32 =
+    |wave| (1.0 + 0.05 * (wave - ENEMY_SUPER_WAVE_START) as f32).min(1.5);
+pub const ENEMY_SUPER_PATTERN_TIME: f32 = 3.0; // seconds per pattern
+pub const ENEMY_SUPER_SPIRAL_ARMS: u32 = 2;
+pub const ENEMY_SUPER_SPIRAL_TURN: f32 = 0.3; // radians per volley
+pub const ENEMY_SUPER_SPIRAL_RELOAD_TIME: f32 = 0.25; // seconds
+pub const ENEMY_SUPER_BURST_COUNT: u32 = 5;
+pub const ENEMY_SUPER_BURST_SPREAD: f32 = 0.6; // radians
+pub const ENEMY_SUPER_BURST_RELOAD_TIME: f32 = 1.0; // seconds
+pub const ENEMY_SUPER_WALL_COUNT: u32 = 9;
+pub const ENEMY_SUPER_WALL_GAP: u32 = 3; // bullets
+pub const ENEMY_SUPER_WALL_RELOAD_TIME: f32 = 1.5; // seconds
 pub const ENEMY_SUPER_HP_MOD: fn(i32) -> f32 = |wave| 5.0 + wave as f32;
 
-pub const ENEMY_SPAWN_RADIUS: f32 = TILES_PER_SCALE as f32; // tiles
+pub const ENEMY_ROOK_RANGE: f32 = 6.0; // tiles
+pub const ENEMY_ROOK_ALIGN_TOLERANCE: f32 = 0.25; // tiles
+pub const ENEMY_ROOK_TELEGRAPH_TIME: f32 = 0.8; // seconds
+pub const ENEMY_ROOK_RELOAD_TIME: f32 = 2.5; // seconds
+pub const ENEMY_ROOK_DASH_SPEED: f32 = 9.0; // tiles per second
+pub const ENEMY_ROOK_DASH_DISTANCE: f32 = TILES_PER_SCALE as f32; // tiles
+pub const ENEMY_ROOK_DAMAGE_MOD: f32 = 2.0;
+pub const ENEMY_ROOK_TRAMPLE_DAMAGE: f32 = 0.25; // percent of the other enemy's max health
+pub const ENEMY_ROOK_TRAMPLE_KNOCKBACK_SPEED: f32 = 6.0; // tiles per second
+pub const ENEMY_ROOK_SLOW_TIME: f32 = 1.0; // seconds
+pub const ENEMY_ROOK_SLOW_SPEED_MOD: f32 = 0.6; // player speed multiplier
+pub const ENEMY_ROOK_HP_MOD: f32 = 1.5;
+pub const ENEMY_ROOK_WAVE_START: i32 = 3;
+pub const ENEMY_ROOK_CHANCE: f32 = 0.15; // percent
+
+pub const ENEMY_KNIGHT_HOP_SIZE: f32 = 1.0; // tiles, length of the short leg of the L
+pub const ENEMY_KNIGHT_HOP_TIME: f32 = 0.3; // seconds
+pub const ENEMY_KNIGHT_TELEGRAPH_TIME: f32 = 0.7; // seconds
+pub const ENEMY_KNIGHT_RELOAD_TIME: f32 = 0.6; // seconds
+pub const ENEMY_KNIGHT_LANDING_RADIUS: f32 = 0.6; // tiles
+pub const ENEMY_KNIGHT_DAMAGE_MOD: f32 = 1.5;
+pub const ENEMY_KNIGHT_WAVE_START: i32 = 4;
+pub const ENEMY_KNIGHT_CHANCE: f32 = 0.15; // percent
+
+pub const ENEMY_BOSS_WAVE_PERIOD: i32 = 5; // boss every N waves
+pub const ENEMY_BOSS_SIZE: f32 = 0.07; // scale
+pub const ENEMY_BOSS_HP_MOD: fn(i32) -> f32 = |wave| 20.0 + 2.0 * wave as f32;
+pub const ENEMY_BOSS_SPEED_MOD: f32 = 0.6;
+pub const ENEMY_BOSS_RANGE: f32 = 8.0; // tiles
+pub const ENEMY_BOSS_MIN_RANGE: f32 = 2.0; // tiles
+pub const ENEMY_BOSS_ARENA_RADIUS: f32 = 7.0; // tiles
+pub const ENEMY_BOSS_ARENA_THICKNESS: f32 = 0.005; // scale
+pub const ENEMY_BOSS_PHASE_THRESHOLDS: [f32; 2] = [0.66, 0.33]; // health ratios for phases 2 and 3
+pub const ENEMY_BOSS_FAN_COUNT: u32 = 5;
+pub const ENEMY_BOSS_FAN_SPREAD: f32 = 0.8; // radians
+pub const ENEMY_BOSS_FAN_RELOAD_TIME: f32 = 1.2; // seconds
+pub const ENEMY_BOSS_RING_COUNT: u32 = 12;
+pub const ENEMY_BOSS_RING_RELOAD_TIME: f32 = 1.5; // seconds
+pub const ENEMY_BOSS_WALL_PERIOD: u32 = 3; // every nth volley in the ring phase is a wall
+pub const ENEMY_BOSS_WALL_COUNT: u32 = 13;
+pub const ENEMY_BOSS_WALL_GAP: u32 = 3; // bullets
+pub const ENEMY_BOSS_FRENZY_SPIRAL_ARMS: u32 = 4;
+pub const ENEMY_BOSS_FRENZY_SPIRAL_TURN: f32 = 0.2; // radians per volley
+pub const ENEMY_BOSS_FRENZY_RELOAD_TIME: f32 = 0.15; // seconds
+pub const ENEMY_BOSS_FRENZY_SPEED_MOD: f32 = 1.5;
+pub const ENEMY_BOSS_BAR_WIDTH: f32 = 0.9; // percent of screen width
+pub const ENEMY_BOSS_BAR_HEIGHT: f32 = 0.03; // scale
+pub const ENEMY_BOSS_BAR_TOP_OFFSET: f32 = 0.01; // scale
+pub const ENEMY_BOSS_BAR_THICKNESS: f32 = 0.005; // scale
+pub const ENEMY_BOSS_BAR_FONT_RATIO: f32 = 0.85; // percent
+
+pub const ENEMY_SPAWN_MARGIN: f32 = 1.0; // tiles outside the visible area
+pub const ENEMY_RECYCLE_RANGE_MULT: f32 = 0.8; // of the screen diagonal
+pub const ENEMY_RECYCLE_SPREAD: f32 = 0.8; // radians around the player's movement
+pub const ENEMY_MAX_LIVE: usize = 150; // regular spawns wait while at the cap
+pub const ENEMY_SPAWN_TELEGRAPH_TIME: f32 = 0.6; // seconds
+pub const ENEMY_SPAWN_TELEGRAPH_THICKNESS: f32 = 0.003; // scale
+pub const ENEMY_SPAWN_TELEGRAPH_INSET: f32 = 0.01; // scale, from the edge of the screen
 pub const ENEMY_RANGED_CHANCE: f32 = 0.33; // percent
 
+pub const ENEMY_FORMATION_WAVE_START: i32 = 2;
+pub const ENEMY_FORMATION_CHANCE: f32 = 0.2; // percent, per spawn
+pub const ENEMY_WALL_COUNT: usize = 7;
+pub const ENEMY_WALL_SPACING: f32 = 0.75; // tiles
+pub const ENEMY_RING_COUNT: usize = 10;
+pub const ENEMY_SQUAD_WAVE_START: i32 = 4;
+pub const ENEMY_SQUAD_COUNT: usize = 4;
+pub const ENEMY_SQUAD_SPACING: f32 = 1.0; // tiles
+pub const ENEMY_SWARM_WAVE_START: i32 = 3;
+pub const ENEMY_SWARM_COUNT: usize = 12;
+pub const ENEMY_SWARM_RADIUS: f32 = 1.0; // tiles
+pub const ENEMY_SWARM_HP_MOD: f32 = 0.35;
+pub const ENEMY_SWARM_SPEED_MOD: f32 = 1.6;
+
+pub const ENEMY_ELITE_WAVE_START: i32 = 3;
+pub const ENEMY_ELITE_CHANCE: fn(i32) -> f32 =
+    |wave| (0.03 * (wave - ENEMY_ELITE_WAVE_START + 1) as f32).clamp(0.0, 0.3);
+pub const ENEMY_ELITE_AFFIX_COUNT: fn(i32) -> usize = |wave| if wave >= 10 { 2 } else { 1 };
+pub const ENEMY_ELITE_XP: i32 = 3; // per affix
+pub const ENEMY_ELITE_OUTLINE_THICKNESS: f32 = 0.003; // scale
+pub const ENEMY_ELITE_FONT_SIZE: f32 = 0.018; // scale
+pub const ENEMY_SHIELDED_HITS: u32 = 3;
+pub const ENEMY_SWIFT_SPEED_MOD: f32 = 1.5;
+pub const ENEMY_SPLITTING_COUNT: usize = 2;
+pub const ENEMY_SPLITTING_HP_MOD: f32 = 0.4;
+pub const ENEMY_SPLITTING_OFFSET: f32 = 0.4; // tiles
+pub const ENEMY_VAMPIRIC_HEAL: f32 = 0.25; // percent of max health per hit
+pub const ENEMY_REFLECTIVE_BULLETS: u32 = 1;
+
+pub const ENEMY_NEIGHBOR_CELL_SIZE: f32 = 1.5; // tiles, >= largest separation/flock distance
+pub const ENEMY_SEPARATION_SPACING: f32 = 1.1; // relative to combined radii
+pub const ENEMY_SEPARATION_STRENGTH: f32 = 4.0; // tiles per second at full overlap
+pub const ENEMY_FLOCK_RADIUS: f32 = 1.5; // tiles
+pub const ENEMY_FLOCK_ALIGNMENT: f32 = 0.3; // relative to speed
+pub const ENEMY_FLOCK_COHESION: f32 = 0.2; // relative to speed
+
 // Note: wave spawning starts at 1
 pub const ENEMY_WAVE_COUNT: fn(i32) -> i32 = |wave| 5 + 3 * (wave - 1);
 
 pub const ENEMY_WAVE_HP: fn(i32) -> f32 = |wave| 10.0 + 8.0 * (wave - 1) as f32;
+pub const WAVE_MIN_HP: f32 = 1.0; // floor for wave file curves
 pub const ENEMY_DAMAGE: f32 = 1.0;
 pub const ENEMY_WAVE_SPEED: fn(i32) -> f32 = |wave| ENEMY_SPEED * (1.0 + 0.015 * (wave - 1) as f32);
 
 pub const XP_PER_LEVEL: fn(i32) -> i32 = |level| 2 * level.pow(2);
+pub const ENEMY_XP: i32 = 1; // pawns and bishops
+pub const ENEMY_STRONG_XP: i32 = 2; // rooks and knights
+pub const ENEMY_SUPER_XP: i32 = 5;
+pub const ENEMY_BOSS_XP: i32 = 10;
+
+pub const PICKUP_MAGNET_RADIUS: f32 = 1.5; // tiles
+pub const PICKUP_MAGNET_UPGRADE: f32 = 0.75; // tiles
+pub const PICKUP_MAGNET_MAX_RADIUS: f32 = 4.5; // tiles
+pub const PICKUP_MAGNET_SPEED: f32 = 5.0; // tiles per second
+pub const PICKUP_SCATTER: f32 = 0.3; // tiles
+pub const PICKUP_XP_SIZE: f32 = 0.006; // scale
+pub const PICKUP_XP_MAX_SIZE_MOD: f32 = 2.0;
+pub const PICKUP_HEALTH_SIZE: f32 = 0.008; // scale
+pub const PICKUP_CARD_SIZE: f32 = 0.012; // scale
+pub const PICKUP_CHIP_SIZE: f32 = 0.008; // scale
+pub const PICKUP_OUTLINE_THICKNESS: f32 = 0.002; // scale
+pub const PICKUP_HEALTH_CHANCE: f32 = 0.04; // percent
+pub const PICKUP_HEALTH_AMOUNT: f32 = 2.0;
+pub const PICKUP_CARD_CHANCE: f32 = 0.005; // percent
+pub const PICKUP_CHIP_CHANCE: f32 = 0.03; // percent
+pub const PICKUP_CHIP_SCORE: i32 = 5;
+pub const PICKUP_LIFETIME: f32 = 30.0; // seconds
+pub const PICKUP_BLINK_TIME: f32 = 5.0; // seconds
+pub const PICKUP_BLINK_PERIOD: f32 = 0.2; // seconds
 
 // x, a, b, c
 // f(x) = ((b - a) / log(c + 1)) * log(x + 1) + a
@@ -177,3 +473,4 @@ pub const AUTO_LOG: fn(f32, f32, f32, f32) -> f32 =
     |x, a, b, c| ((b - a) / (c + 1.0).log10()) * (x + 1.0).log10() + a;
 pub const ENEMY_WAVE_SPAWN_RATE: fn(i32) -> f32 =
     |wave| AUTO_LOG(wave as f32, 1.0 / 5.0, 1.0 / 1.5, 12.0);
+pub const WAVE_MIN_SPAWN_RATE: f32 = 0.05; // enemies per second, floor for wave file curves
@@ -1,7 +1,7 @@
 use macroquad::prelude as mq;
 use macroquad::rand::ChooseRandom;
 
-use crate::{consts, powerup, weapon};
+use crate::{colors, consts, weapon};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum Suit {
@@ -34,15 +34,117 @@ impl Suit {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Enchantment {
+    Foil,   // more damage
+    Gilded, // more xp on kill
+    Glass,  // a lot more damage, but breaks after a number of shots
+    Steel,  // pierce and range
+}
+
+impl Enchantment {
+    pub fn random() -> Self {
+        match mq::rand::gen_range(0, 4) {
+            0 => Enchantment::Foil,
+            1 => Enchantment::Gilded,
+            2 => Enchantment::Glass,
+            3 => Enchantment::Steel,
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Enchantment::Foil => "Foil",
+            Enchantment::Gilded => "Gilded",
+            Enchantment::Glass => "Glass",
+            Enchantment::Steel => "Steel",
+        }
+    }
+
+    pub fn description(&self) -> String {
+        // modifiers shown as a percent increase
+        let percent = |modifier: f32| ((modifier - 1.0) * 100.0).round();
+        match self {
+            Enchantment::Foil => {
+                format!("+{}% damage", percent(consts::ENCHANTMENT_FOIL_DAMAGE_MOD))
+            }
+            Enchantment::Gilded => format!("+{} xp on kill", consts::ENCHANTMENT_GILDED_XP),
+            Enchantment::Glass => format!(
+                "x{} damage, breaks after {} shots",
+                consts::ENCHANTMENT_GLASS_DAMAGE_MOD,
+                consts::ENCHANTMENT_GLASS_SHOTS
+            ),
+            Enchantment::Steel => format!(
+                "+{} pierce, +{}% range",
+                consts::ENCHANTMENT_STEEL_PIERCE,
+                percent(consts::ENCHANTMENT_STEEL_RANGE_MOD)
+            ),
+        }
+    }
+
+    pub fn color(&self) -> mq::Color {
+        match self {
+            Enchantment::Foil => colors::NORD8,
+            Enchantment::Gilded => colors::NORD13,
+            Enchantment::Glass => colors::NORD7,
+            Enchantment::Steel => colors::NORD3,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub struct Card {
     pub suit: Suit,
     pub value: u8,
+    pub enchantment: Option<Enchantment>,
+    pub fused: bool, // only exists in the hand, never part of the deck
 }
 
 impl Card {
     pub fn new(suit: Suit, value: u8) -> Self {
-        Self { suit, value }
+        Self {
+            suit,
+            value,
+            enchantment: None,
+            fused: false,
+        }
+    }
+
+    pub fn with_enchantment(&self, enchantment: Enchantment) -> Self {
+        Self {
+            enchantment: Some(enchantment),
+            ..*self
+        }
+    }
+
+    pub fn draw(&self, cards_texture: &mq::Texture2D, rect: mq::Rect, scale: f32) {
+        mq::draw_texture_ex(
+            cards_texture,
+            rect.x,
+            rect.y,
+            mq::WHITE,
+            mq::DrawTextureParams {
+                dest_size: Some(mq::Vec2::new(rect.w, rect.h)),
+                source: Some(self.get_texture_source()),
+                ..Default::default()
+            },
+        );
+
+        if let Some(enchantment) = self.enchantment {
+            let mut overlay_color = enchantment.color();
+            overlay_color.a = consts::ENCHANTMENT_OVERLAY_ALPHA;
+
+            mq::draw_rectangle(rect.x, rect.y, rect.w, rect.h, overlay_color);
+            mq::draw_rectangle_lines(
+                rect.x,
+                rect.y,
+                rect.w,
+                rect.h,
+                (consts::ENCHANTMENT_OUTLINE_THICKNESS * scale).max(1.0),
+                enchantment.color(),
+            );
+        }
     }
 
     pub fn get_texture_source(&self) -> mq::Rect {
@@ -50,8 +152,9 @@ impl Card {
             Self {
                 suit: Suit::Joker,
                 value,
+                ..
             } => (0, 2 + *value as usize),
-            Self { suit, value } => {
+            Self { suit, value, .. } => {
                 let suit_idx = match suit {
                     Suit::Hearts => 0,
                     Suit::Spades => 1,
@@ -75,6 +178,38 @@ impl Card {
         mq::Rect::new(x, y, consts::CARD_PX_WIDTH, consts::CARD_PX_HEIGHT)
     }
 
+    pub fn sort_key(&self) -> (u8, u8) {
+        (self.suit as u8, self.value)
+    }
+
+    pub fn name(&self) -> String {
+        let suit = match self.suit {
+            Suit::Spades => "Spades",
+            Suit::Hearts => "Hearts",
+            Suit::Clubs => "Clubs",
+            Suit::Diamonds => "Diamonds",
+            Suit::Joker => {
+                return if self.is_red() {
+                    "Red Joker".to_owned()
+                } else {
+                    "Black Joker".to_owned()
+                };
+            }
+        };
+        let value = match self.value {
+            1 => "Ace".to_owned(),
+            11 => "Jack".to_owned(),
+            12 => "Queen".to_owned(),
+            13 => "King".to_owned(),
+            value => value.to_string(),
+        };
+
+        match self.enchantment {
+            Some(enchantment) => format!("{} {} of {}", enchantment.name(), value, suit),
+            None => format!("{} of {}", value, suit),
+        }
+    }
+
     pub fn is_red(&self) -> bool {
         match self.suit {
             Suit::Hearts | Suit::Diamonds => true,
@@ -83,27 +218,18 @@ impl Card {
         }
     }
 
-    pub fn heal_amount(&self, powerups: &powerup::Powerups) -> f32 {
-        if self.suit == Suit::Hearts {
-            powerups.hearts_heal_amount()
-        } else {
-            0.0
-        }
-    }
-
-    pub fn stun_time(&self, powerups: &powerup::Powerups) -> f32 {
-        if self.suit == Suit::Clubs {
-            powerups.clubs_stun_time()
+    pub fn knockback_speed(&self) -> f32 {
+        if self.suit != Suit::Joker && self.value == 13 {
+            consts::KING_KNOCKBACK_SPEED
         } else {
             0.0
         }
     }
 
-    pub fn damage(&self, powerups: Option<&powerup::Powerups>) -> f32 {
+    pub fn damage(&self) -> f32 {
         let mut damage = match self {
             Self {
-                suit: Suit::Joker,
-                value: _,
+                suit: Suit::Joker, ..
             } => {
                 if mq::rand::gen_range::<u8>(0, 2) == 0 {
                     f32::INFINITY
@@ -111,7 +237,7 @@ impl Card {
                     0.0
                 }
             }
-            Self { suit: _, value } => {
+            Self { value, .. } => {
                 if self.is_face() {
                     *value as f32 * 2.0
                 } else if self.is_ace() {
@@ -121,24 +247,113 @@ impl Card {
                 }
             }
         };
-        if let Some(powerups) = powerups {
-            if self.suit == Suit::Spades {
-                damage *= powerups.spades_damage_mod();
-            }
+        match self.enchantment {
+            Some(Enchantment::Foil) => damage *= consts::ENCHANTMENT_FOIL_DAMAGE_MOD,
+            Some(Enchantment::Glass) => damage *= consts::ENCHANTMENT_GLASS_DAMAGE_MOD,
+            _ => {}
         }
 
         damage
     }
 
+    pub fn extra_pierce(&self) -> i32 {
+        if self.enchantment == Some(Enchantment::Steel) {
+            consts::ENCHANTMENT_STEEL_PIERCE
+        } else {
+            0
+        }
+    }
+
+    pub fn kill_xp(&self) -> i32 {
+        if self.enchantment == Some(Enchantment::Gilded) {
+            consts::ENCHANTMENT_GILDED_XP
+        } else {
+            0
+        }
+    }
+
+    pub fn max_shots(&self) -> Option<u32> {
+        if self.enchantment == Some(Enchantment::Glass) {
+            Some(consts::ENCHANTMENT_GLASS_SHOTS)
+        } else {
+            None
+        }
+    }
+
+    // shots before the card is discarded in the deck as ammo game mode
+    pub fn ammo(&self) -> u32 {
+        match self.suit {
+            Suit::Joker => consts::AMMO_JOKER_SHOTS,
+            _ if self.is_ace() => consts::AMMO_ACE_SHOTS,
+            _ if self.is_face() => consts::AMMO_FACE_SHOTS,
+            _ => self.value as u32 * consts::AMMO_SHOTS_PER_VALUE,
+        }
+    }
+
     pub fn get_weapon(&self) -> weapon::Weapon {
-        if self.suit == Suit::Joker {
+        let mut weapon = if self.suit == Suit::Joker {
             consts::JOKER_WEAPON
-        } else if self.is_ace() {
-            consts::ACE_WEAPON
-        } else if self.is_face() {
-            consts::FACE_WEAPON
         } else {
-            consts::ELSE_WEAPON
+            match self.value {
+                1 => consts::ACE_WEAPON,
+                13 => consts::KING_WEAPON,
+                12 => consts::QUEEN_WEAPON,
+                11 => consts::JACK_WEAPON,
+                9 => consts::NINE_WEAPON,
+                8 => consts::EIGHT_WEAPON,
+                7 => consts::SEVEN_WEAPON,
+                _ => consts::ELSE_WEAPON,
+            }
+        };
+        if self.enchantment == Some(Enchantment::Steel) {
+            weapon.range *= consts::ENCHANTMENT_STEEL_RANGE_MOD;
+        }
+
+        weapon
+    }
+
+    pub fn strength(&self) -> u8 {
+        match self.suit {
+            Suit::Joker => 0,
+            _ if self.is_ace() => 14,
+            _ => self.value,
+        }
+    }
+
+    pub fn joker_damage_mod(&self) -> f32 {
+        if self.is_red() {
+            consts::JOKER_RED_DAMAGE_MOD
+        } else {
+            consts::JOKER_BLACK_DAMAGE_MOD
+        }
+    }
+
+    pub fn joker_fire_rate_mod(&self) -> f32 {
+        if self.is_red() {
+            consts::JOKER_RED_FIRE_RATE_MOD
+        } else {
+            consts::JOKER_BLACK_FIRE_RATE_MOD
+        }
+    }
+
+    pub fn can_fuse(&self) -> bool {
+        self.suit != Suit::Joker && !self.is_ace()
+    }
+
+    pub fn fuse(&self, other: &Card) -> Card {
+        // 11-13 become face cards, anything higher becomes an ace
+        let value = self.value + other.value;
+        let value = if value > consts::FUSE_MAX_VALUE {
+            1
+        } else {
+            value
+        };
+
+        Card {
+            suit: self.suit,
+            value,
+            enchantment: self.enchantment.or(other.enchantment),
+            fused: true,
         }
     }
 
@@ -153,7 +368,10 @@ impl Card {
 
 pub struct Deck {
     all_cards: Vec<Card>,
-    cards: Vec<Card>,
+    draw_pile: Vec<Card>,
+    discard_pile: Vec<Card>,
+    in_hand: Vec<Card>, // drawn but not discarded yet (hand and card choices)
+    removed_in_hand: Vec<Card>, // removed from the deck while drawn, dropped on discard
 }
 
 impl Deck {
@@ -167,21 +385,26 @@ impl Deck {
         all_cards.push(Card::new(Suit::Joker, 0));
         all_cards.push(Card::new(Suit::Joker, 1));
 
-        let cards = all_cards.clone();
+        let draw_pile = all_cards.clone();
 
-        let mut deck = Self { all_cards, cards };
+        let mut deck = Self {
+            all_cards,
+            draw_pile,
+            discard_pile: Vec::new(),
+            in_hand: Vec::new(),
+            removed_in_hand: Vec::new(),
+        };
         deck.shuffle();
         deck
     }
 
     pub fn draw_card(&mut self) -> Card {
-        let card = self.cards.pop();
-        if let Some(card) = card {
-            card
-        } else {
+        if self.draw_pile.is_empty() {
             self.refresh();
-            self.cards.pop().unwrap()
         }
+        let card = self.draw_pile.pop().unwrap();
+        self.in_hand.push(card);
+        card
     }
 
     pub fn draw_three_cards(&mut self) -> Vec<Card> {
@@ -192,16 +415,343 @@ impl Deck {
         cards
     }
 
+    pub fn return_card(&mut self, card: Card) {
+        // put a drawn card back into the draw pile
+        if let Some(i) = self.in_hand.iter().position(|c| *c == card) {
+            self.in_hand.remove(i);
+            self.draw_pile.push(card);
+        }
+    }
+
+    pub fn discard(&mut self, card: Card) {
+        // cards that were never drawn (ex: fused cards) are not part of the deck
+        if card.fused {
+            return;
+        }
+        if let Some(i) = self.in_hand.iter().position(|c| *c == card) {
+            self.in_hand.remove(i);
+
+            if let Some(j) = self.removed_in_hand.iter().position(|c| *c == card) {
+                self.removed_in_hand.remove(j);
+            } else {
+                self.discard_pile.push(card);
+            }
+        }
+    }
+
+    pub fn all_cards(&self) -> &[Card] {
+        &self.all_cards
+    }
+
+    pub fn can_remove_cards(&self, count: usize) -> bool {
+        self.all_cards.len() >= consts::DECK_MIN_SIZE + count
+    }
+
     pub fn add_card(&mut self, card: Card) {
-        self.cards.push(card);
+        // permanently add a card for the rest of the run
+        self.all_cards.push(card);
+        self.draw_pile.push(card);
+        self.shuffle();
+    }
+
+    pub fn remove_card(&mut self, card: Card) {
+        // permanently remove a card for the rest of the run
+        if card.fused {
+            return;
+        }
+        let Some(i) = self.all_cards.iter().position(|c| *c == card) else {
+            return;
+        };
+        self.all_cards.remove(i);
+
+        if let Some(i) = self.draw_pile.iter().position(|c| *c == card) {
+            self.draw_pile.remove(i);
+        } else if let Some(i) = self.discard_pile.iter().position(|c| *c == card) {
+            self.discard_pile.remove(i);
+        } else {
+            // currently drawn, removed once it is discarded
+            self.removed_in_hand.push(card);
+        }
+    }
+
+    pub fn remove_value(&mut self, value: u8) {
+        let cards = self
+            .all_cards
+            .iter()
+            .filter(|card| card.suit != Suit::Joker && card.value == value)
+            .copied()
+            .collect::<Vec<_>>();
+        for card in cards {
+            self.remove_card(card);
+        }
+    }
+
+    pub fn transform_card(&mut self, card: Card, new_card: Card) {
+        if self.all_cards.contains(&card) {
+            self.remove_card(card);
+            self.add_card(new_card);
+        }
+    }
+
+    pub fn card_count(&self) -> usize {
+        self.all_cards.len()
+    }
+
+    pub fn draw_pile(&self) -> &[Card] {
+        &self.draw_pile
+    }
+
+    pub fn discard_pile(&self) -> &[Card] {
+        &self.discard_pile
     }
 
     fn refresh(&mut self) {
-        self.cards = self.all_cards.clone();
+        self.draw_pile.append(&mut self.discard_pile);
         self.shuffle();
     }
 
     pub fn shuffle(&mut self) {
-        self.cards.shuffle();
+        self.draw_pile.shuffle();
+    }
+}
+
+pub fn draw_deck_viewer(deck: &Deck, cards_texture: &mq::Texture2D, font: &mq::Font, scale: f32) {
+    mq::draw_rectangle(
+        0.0,
+        0.0,
+        mq::screen_width(),
+        mq::screen_height(),
+        colors::NORD0_ALPHA,
+    );
+
+    let padding = consts::DECK_VIEWER_PADDING * scale;
+    let font_size = (consts::DECK_VIEWER_FONT_SIZE * scale).round() as u16;
+    let header_height = consts::DECK_VIEWER_FONT_SIZE * scale * consts::DECK_VIEWER_HEADER_RATIO;
+
+    let columns = consts::DECK_VIEWER_COLUMNS;
+    let rows_per_pile = deck.card_count().div_ceil(columns).max(1);
+    let total_rows = rows_per_pile * 2;
+
+    let max_width = mq::screen_width() - 2.0 * padding;
+    let max_height = mq::screen_height() - 2.0 * padding - 3.0 * header_height;
+
+    let card_ratio = consts::CARD_PX_WIDTH / consts::CARD_PX_HEIGHT;
+    let card_width =
+        (max_width / (columns as f32 + consts::DECK_VIEWER_SPACING * (columns - 1) as f32)).min(
+            max_height
+                / (total_rows as f32 + consts::DECK_VIEWER_SPACING * (total_rows - 1) as f32)
+                * card_ratio,
+        );
+    let card_height = card_width / card_ratio;
+    let spacing = consts::DECK_VIEWER_SPACING * card_width;
+
+    let grid_width = columns as f32 * card_width + (columns - 1) as f32 * spacing;
+    let start_x = (mq::screen_width() - grid_width) / 2.0;
+
+    let mut y = padding;
+    for (title, pile) in [
+        ("Draw pile", deck.draw_pile()),
+        ("Discard pile", deck.discard_pile()),
+    ] {
+        let aces = pile.iter().filter(|card| card.is_ace()).count();
+        let jokers = pile.iter().filter(|card| card.suit == Suit::Joker).count();
+        let text = format!(
+            "{} ({}/{})  Aces: {}  Jokers: {}",
+            title,
+            pile.len(),
+            deck.card_count(),
+            aces,
+            jokers
+        );
+        let text_dims = mq::measure_text(&text, Some(font), font_size, 1.0);
+        mq::draw_text_ex(
+            &text,
+            start_x,
+            y + text_dims.offset_y,
+            mq::TextParams {
+                font: Some(font),
+                font_size,
+                font_scale: 1.0,
+                color: colors::NORD6,
+                ..mq::TextParams::default()
+            },
+        );
+        y += header_height;
+
+        // sorted so the draw order isn't revealed
+        let mut cards = pile.to_vec();
+        cards.sort_by_key(Card::sort_key);
+
+        for (i, card) in cards.iter().enumerate() {
+            let x = start_x + (i % columns) as f32 * (card_width + spacing);
+            let card_y = y + (i / columns) as f32 * (card_height + spacing);
+
+            card.draw(
+                cards_texture,
+                mq::Rect::new(x, card_y, card_width, card_height),
+                scale,
+            );
+        }
+
+        y += rows_per_pile as f32 * (card_height + spacing);
+    }
+
+    let text = "Press V, Esc or tap to go back";
+    let text_dims = mq::measure_text(text, Some(font), font_size, 1.0);
+    mq::draw_text_ex(
+        text,
+        (mq::screen_width() - text_dims.width) / 2.0,
+        y + text_dims.offset_y,
+        mq::TextParams {
+            font: Some(font),
+            font_size,
+            font_scale: 1.0,
+            color: colors::NORD4,
+            ..mq::TextParams::default()
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // every card is in exactly one of the zones
+    fn assert_zones_add_up(deck: &Deck, in_hand: usize) {
+        assert_eq!(
+            deck.draw_pile().len() + deck.discard_pile().len() + in_hand,
+            deck.card_count()
+        );
+    }
+
+    #[test]
+    fn draw_discard_and_return() {
+        let mut deck = Deck::new();
+        assert_eq!(deck.card_count(), 54);
+        assert_eq!(deck.draw_pile().len(), 54);
+
+        let card = deck.draw_card();
+        assert_eq!(deck.draw_pile().len(), 53);
+        assert_zones_add_up(&deck, 1);
+
+        deck.discard(card);
+        assert!(deck.discard_pile() == [card]);
+        assert_zones_add_up(&deck, 0);
+
+        let card = deck.draw_card();
+        deck.return_card(card);
+        assert_eq!(deck.draw_pile().len(), 53);
+        assert!(deck.draw_pile().last() == Some(&card));
+        assert_zones_add_up(&deck, 0);
+    }
+
+    #[test]
+    fn discarding_a_card_that_was_never_drawn_does_nothing() {
+        let mut deck = Deck::new();
+        // ex: a fused card
+        deck.discard(Card::new(Suit::Spades, 14));
+        assert!(deck.discard_pile().is_empty());
+        assert_zones_add_up(&deck, 0);
+    }
+
+    #[test]
+    fn fused_cards_leave_the_deck_alone() {
+        let mut deck = Deck::new();
+        let fused = Card::new(Suit::Hearts, 3).fuse(&Card::new(Suit::Hearts, 4));
+        let real = Card::new(Suit::Hearts, 7);
+        assert!(fused != real);
+
+        deck.remove_card(fused);
+        deck.discard(fused);
+        assert_eq!(deck.card_count(), 54);
+        assert!(deck.all_cards().contains(&real));
+        assert!(deck.draw_pile().contains(&real));
+        assert!(deck.discard_pile().is_empty());
+    }
+
+    #[test]
+    fn draw_pile_refills_from_the_discard_pile() {
+        let mut deck = Deck::new();
+        for _ in 0..54 {
+            let card = deck.draw_card();
+            deck.discard(card);
+        }
+        assert!(deck.draw_pile().is_empty());
+
+        deck.draw_card();
+        assert_eq!(deck.draw_pile().len(), 53);
+        assert!(deck.discard_pile().is_empty());
+    }
+
+    #[test]
+    fn add_and_remove_cards() {
+        let mut deck = Deck::new();
+        let card = Card::new(Suit::Hearts, 7);
+
+        deck.add_card(card);
+        assert_eq!(deck.card_count(), 55);
+        assert_eq!(deck.draw_pile().iter().filter(|c| **c == card).count(), 2);
+
+        deck.remove_card(card);
+        deck.remove_card(card);
+        assert_eq!(deck.card_count(), 53);
+        assert!(!deck.draw_pile().contains(&card));
+        assert_zones_add_up(&deck, 0);
+
+        // already gone
+        deck.remove_card(card);
+        assert_eq!(deck.card_count(), 53);
+
+        deck.remove_value(2);
+        assert_eq!(deck.card_count(), 49);
+        let is_two = |c: &Card| c.suit != Suit::Joker && c.value == 2;
+        assert!(!deck.all_cards().iter().any(is_two));
+        assert_zones_add_up(&deck, 0);
+    }
+
+    #[test]
+    fn removing_a_discarded_card() {
+        let mut deck = Deck::new();
+        let card = deck.draw_card();
+        deck.discard(card);
+
+        deck.remove_card(card);
+        assert!(deck.discard_pile().is_empty());
+        assert_zones_add_up(&deck, 0);
+    }
+
+    #[test]
+    fn removing_a_drawn_card_drops_it_on_discard() {
+        let mut deck = Deck::new();
+        let card = deck.draw_card();
+
+        deck.remove_card(card);
+        assert_eq!(deck.card_count(), 53);
+        assert_zones_add_up(&deck, 0);
+
+        deck.discard(card);
+        assert!(deck.discard_pile().is_empty());
+        assert_zones_add_up(&deck, 0);
+    }
+
+    #[test]
+    fn transforming_a_drawn_card() {
+        let mut deck = Deck::new();
+        let mut card = deck.draw_card();
+        while card.suit == Suit::Joker {
+            deck.discard(card);
+            card = deck.draw_card();
+        }
+        let joker = Card::new(Suit::Joker, 0);
+
+        deck.transform_card(card, joker);
+        assert_eq!(deck.card_count(), 54);
+        assert_eq!(deck.all_cards().iter().filter(|c| **c == joker).count(), 2);
+
+        // the old card is still in hand until it is discarded
+        deck.discard(card);
+        assert!(!deck.discard_pile().contains(&card));
+        assert!(!deck.all_cards().contains(&card));
+        assert_zones_add_up(&deck, 0);
     }
 }
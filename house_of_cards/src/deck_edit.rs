@@ -0,0 +1,141 @@
+use macroquad::prelude as mq;
+use macroquad::rand::ChooseRandom;
+
+use crate::{colors, consts, deck, player, powerup, stats};
+
+#[derive(Clone, Copy)]
+pub enum DeckEdit {
+    RemoveValue(u8),                  // remove every card of this value
+    Duplicate(deck::Card),            // add another copy of a card
+    Jokerize(deck::Card, deck::Card), // (card, joker) turn a card into a joker
+    Enchant(deck::Card, deck::Enchantment),
+    GrowHand,
+    ShrinkHand,
+    Magnet, // pick up from further away
+}
+
+impl DeckEdit {
+    pub fn pick_three(deck: &deck::Deck, player: &player::Player) -> Vec<DeckEdit> {
+        // at most one of each type of edit
+        let mut edits = Vec::with_capacity(6);
+        let hand = &player.hand;
+
+        let value = mq::rand::gen_range(2, consts::DECK_EDIT_REMOVE_MAX_VALUE + 1);
+        let remove_count = deck
+            .all_cards()
+            .iter()
+            .filter(|card| card.suit != deck::Suit::Joker && card.value == value)
+            .count();
+        if remove_count > 0 && deck.can_remove_cards(remove_count) {
+            edits.push(DeckEdit::RemoveValue(value));
+        }
+
+        let suited_cards = deck
+            .all_cards()
+            .iter()
+            .filter(|card| card.suit != deck::Suit::Joker)
+            .copied()
+            .collect::<Vec<_>>();
+
+        if let Some(card) = suited_cards.choose() {
+            edits.push(DeckEdit::Duplicate(*card));
+        }
+
+        if let Some(card) = suited_cards.choose() {
+            let joker = deck::Card::new(deck::Suit::Joker, mq::rand::gen_range(0, 2));
+            edits.push(DeckEdit::Jokerize(*card, joker));
+        }
+
+        let plain_cards = suited_cards
+            .iter()
+            .filter(|card| card.enchantment.is_none())
+            .copied()
+            .collect::<Vec<_>>();
+        if let Some(card) = plain_cards.choose() {
+            edits.push(DeckEdit::Enchant(*card, deck::Enchantment::random()));
+        }
+
+        // only one hand size change at a time
+        let can_change_hand = [
+            (hand.can_grow(), DeckEdit::GrowHand),
+            (hand.can_shrink(), DeckEdit::ShrinkHand),
+        ]
+        .into_iter()
+        .filter(|(can_change, _)| *can_change)
+        .map(|(_, edit)| edit)
+        .collect::<Vec<_>>();
+        if let Some(edit) = can_change_hand.choose() {
+            edits.push(*edit);
+        }
+
+        if player.stats.pickup_radius() < consts::PICKUP_MAGNET_MAX_RADIUS {
+            edits.push(DeckEdit::Magnet);
+        }
+
+        edits.shuffle();
+        edits.truncate(3);
+        edits
+    }
+
+    pub fn apply(&self, deck: &mut deck::Deck, player: &mut player::Player) {
+        let hand = &mut player.hand;
+        match *self {
+            DeckEdit::RemoveValue(value) => deck.remove_value(value),
+            DeckEdit::Duplicate(card) => deck.add_card(card),
+            DeckEdit::Jokerize(card, joker) => deck.transform_card(card, joker),
+            DeckEdit::Enchant(card, enchantment) => {
+                deck.transform_card(card, card.with_enchantment(enchantment))
+            }
+            DeckEdit::GrowHand => hand.grow(deck),
+            DeckEdit::ShrinkHand => hand.shrink(deck),
+            DeckEdit::Magnet => player.stats.add_modifier(
+                stats::Source::DeckEdit,
+                stats::Scope::All,
+                stats::Modifier::Add(stats::Stat::PickupRadius, consts::PICKUP_MAGNET_UPGRADE),
+            ),
+        }
+    }
+}
+
+impl powerup::Choice for DeckEdit {
+    fn color(&self) -> mq::Color {
+        match self {
+            DeckEdit::RemoveValue(_) => colors::NORD11,
+            DeckEdit::Duplicate(_) => colors::NORD10,
+            DeckEdit::Jokerize(_, _) => colors::NORD15,
+            DeckEdit::Enchant(_, enchantment) => enchantment.color(),
+            DeckEdit::GrowHand => colors::NORD14,
+            DeckEdit::ShrinkHand => colors::NORD12,
+            DeckEdit::Magnet => colors::NORD15,
+        }
+    }
+
+    fn main_text(&self) -> Vec<String> {
+        match self {
+            DeckEdit::RemoveValue(value) => {
+                vec!["Remove:".to_owned(), format!("all {}s", value)]
+            }
+            DeckEdit::Duplicate(card) => vec!["Duplicate:".to_owned(), card.name()],
+            DeckEdit::Jokerize(card, _) => vec!["Jokerize:".to_owned(), card.name()],
+            DeckEdit::Enchant(card, enchantment) => {
+                vec![format!("{}:", enchantment.name()), card.name()]
+            }
+            DeckEdit::GrowHand => vec!["Hand:".to_owned(), "+1 card".to_owned()],
+            DeckEdit::ShrinkHand => vec!["Hand:".to_owned(), "-1 card".to_owned()],
+            DeckEdit::Magnet => vec!["Magnet:".to_owned(), "+range".to_owned()],
+        }
+    }
+
+    fn sub_text(&self) -> Vec<String> {
+        let text = match self {
+            DeckEdit::RemoveValue(_) => "removed from the deck".to_owned(),
+            DeckEdit::Duplicate(_) => "adds a copy to the deck".to_owned(),
+            DeckEdit::Jokerize(_, _) => "becomes a joker".to_owned(),
+            DeckEdit::Enchant(_, enchantment) => enchantment.description(),
+            DeckEdit::GrowHand => "hold one more card".to_owned(),
+            DeckEdit::ShrinkHand => "hold one less card".to_owned(),
+            DeckEdit::Magnet => "pick up drops from further".to_owned(),
+        };
+        vec![text, "for the rest of the run".to_owned()]
+    }
+}
@@ -1,27 +1,14 @@
 use macroquad::prelude as mq;
+use macroquad::rand::ChooseRandom;
+use std::collections::VecDeque;
 
-use crate::{bullet, camera, colors, consts, damage_number, hitbox, player, timer, util};
-
-pub struct EnemyStunned {
-    pub time_remaining: f32,
-}
-
-impl EnemyStunned {
-    pub fn new() -> Self {
-        Self {
-            time_remaining: 0.0,
-        }
-    }
-
-    pub fn update(&mut self, delta: f32) {
-        self.time_remaining -= delta;
-        self.time_remaining = self.time_remaining.max(0.0);
-    }
-
-    pub fn is_stunned(&self) -> bool {
-        self.time_remaining > 0.0
-    }
-}
+use crate::{
+    affix, bullet,
+    bullet_pattern::BulletPattern,
+    camera, colors, consts, damage_number,
+    hitbox::{self, Circle},
+    pickup, player, spatial_hash, status_effect, timer, util, wave_config,
+};
 
 pub struct EnemyAttack {
     time_until_next_attack: f32,
@@ -45,19 +32,37 @@ impl EnemyAttack {
     }
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Clone, Copy)]
 pub enum EnemyType {
     Melee,
     Ranged,
     Super,
+    Rook,
+    Knight,
+    King, // boss
 }
 
 impl EnemyType {
+    // names used by the wave file, the boss can't be spawned from it
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pawn" => Some(EnemyType::Melee),
+            "bishop" => Some(EnemyType::Ranged),
+            "queen" => Some(EnemyType::Super),
+            "rook" => Some(EnemyType::Rook),
+            "knight" => Some(EnemyType::Knight),
+            _ => None,
+        }
+    }
+
     fn range(&self) -> f32 {
         match self {
             EnemyType::Melee => consts::ENEMY_MELEE_RANGE,
             EnemyType::Ranged => consts::ENEMY_RANGED_RANGE,
             EnemyType::Super => consts::ENEMY_SUPER_RANGE,
+            EnemyType::Rook => consts::ENEMY_ROOK_RANGE,
+            EnemyType::Knight => consts::ENEMY_KNIGHT_LANDING_RADIUS,
+            EnemyType::King => consts::ENEMY_BOSS_RANGE,
         }
     }
 
@@ -66,6 +71,9 @@ impl EnemyType {
             EnemyType::Melee => consts::ENEMY_MELEE_CHARGE_TIME,
             EnemyType::Ranged => consts::ENEMY_RANGED_CHARGE_TIME,
             EnemyType::Super => -1.0,
+            EnemyType::Rook => consts::ENEMY_ROOK_TELEGRAPH_TIME,
+            EnemyType::Knight => consts::ENEMY_KNIGHT_TELEGRAPH_TIME,
+            EnemyType::King => -1.0,
         }
     }
 
@@ -74,6 +82,9 @@ impl EnemyType {
             EnemyType::Melee => consts::ENEMY_MELEE_RELOAD_TIME,
             EnemyType::Ranged => consts::ENEMY_RANGED_RELOAD_TIME,
             EnemyType::Super => -1.0,
+            EnemyType::Rook => consts::ENEMY_ROOK_RELOAD_TIME,
+            EnemyType::Knight => consts::ENEMY_KNIGHT_RELOAD_TIME,
+            EnemyType::King => -1.0,
         }
     }
 
@@ -82,27 +93,232 @@ impl EnemyType {
             EnemyType::Melee => consts::ENEMY_SIZE,
             EnemyType::Ranged => consts::ENEMY_SIZE,
             EnemyType::Super => consts::ENEMY_SUPER_SIZE,
+            EnemyType::Rook => consts::ENEMY_SIZE,
+            EnemyType::Knight => consts::ENEMY_SIZE,
+            EnemyType::King => consts::ENEMY_BOSS_SIZE,
+        }
+    }
+
+    fn xp(&self) -> i32 {
+        match self {
+            EnemyType::Melee => consts::ENEMY_XP,
+            EnemyType::Ranged => consts::ENEMY_XP,
+            EnemyType::Super => consts::ENEMY_SUPER_XP,
+            EnemyType::Rook => consts::ENEMY_STRONG_XP,
+            EnemyType::Knight => consts::ENEMY_STRONG_XP,
+            EnemyType::King => consts::ENEMY_BOSS_XP,
         }
     }
 
+    // only enemies that hit the player directly can heal from it
+    fn can_be_vampiric(&self) -> bool {
+        !matches!(self, EnemyType::Ranged | EnemyType::Super | EnemyType::King)
+    }
+
     fn is_melee(&self) -> bool {
         match self {
             EnemyType::Melee => true,
             EnemyType::Ranged => false,
             EnemyType::Super => false,
+            EnemyType::Rook => false,
+            EnemyType::Knight => false,
+            EnemyType::King => false,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum BossPhase {
+    Fan,
+    Ring,
+    Frenzy,
+}
+
+impl BossPhase {
+    fn from_health_ratio(health_ratio: f32) -> Self {
+        let [ring_threshold, frenzy_threshold] = consts::ENEMY_BOSS_PHASE_THRESHOLDS;
+        if health_ratio > ring_threshold {
+            BossPhase::Fan
+        } else if health_ratio > frenzy_threshold {
+            BossPhase::Ring
+        } else {
+            BossPhase::Frenzy
+        }
+    }
+
+    fn number(&self) -> i32 {
+        match self {
+            BossPhase::Fan => 1,
+            BossPhase::Ring => 2,
+            BossPhase::Frenzy => 3,
+        }
+    }
+
+    fn pattern(&self, volley: u32) -> BulletPattern {
+        match self {
+            BossPhase::Fan => BulletPattern::AimedBurst(
+                consts::ENEMY_BOSS_FAN_COUNT,
+                consts::ENEMY_BOSS_FAN_SPREAD,
+            ),
+            BossPhase::Ring if volley.is_multiple_of(consts::ENEMY_BOSS_WALL_PERIOD) => {
+                BulletPattern::Wall(consts::ENEMY_BOSS_WALL_COUNT, consts::ENEMY_BOSS_WALL_GAP)
+            }
+            BossPhase::Ring => BulletPattern::Ring(consts::ENEMY_BOSS_RING_COUNT),
+            BossPhase::Frenzy => BulletPattern::Spiral(
+                consts::ENEMY_BOSS_FRENZY_SPIRAL_ARMS,
+                consts::ENEMY_BOSS_FRENZY_SPIRAL_TURN,
+            ),
+        }
+    }
+
+    fn reload_time(&self) -> f32 {
+        match self {
+            BossPhase::Fan => consts::ENEMY_BOSS_FAN_RELOAD_TIME,
+            BossPhase::Ring => consts::ENEMY_BOSS_RING_RELOAD_TIME,
+            BossPhase::Frenzy => consts::ENEMY_BOSS_FRENZY_RELOAD_TIME,
+        }
+    }
+
+    fn speed_mod(&self) -> f32 {
+        match self {
+            BossPhase::Frenzy => consts::ENEMY_BOSS_FRENZY_SPEED_MOD,
+            _ => 1.0,
         }
     }
 }
 
-enum EnemyShotType {
-    None,
-    Standard,
-    Spread,
+// queens cycle through their patterns, spending a while on each
+#[derive(Clone, Copy)]
+enum SuperPattern {
+    Spiral,
+    Burst,
+    Wall,
+}
+
+impl SuperPattern {
+    fn from_time(time: f32) -> Self {
+        match (time / consts::ENEMY_SUPER_PATTERN_TIME) as u32 % 3 {
+            0 => SuperPattern::Spiral,
+            1 => SuperPattern::Burst,
+            _ => SuperPattern::Wall,
+        }
+    }
+
+    fn pattern(&self) -> BulletPattern {
+        match self {
+            SuperPattern::Spiral => BulletPattern::Spiral(
+                consts::ENEMY_SUPER_SPIRAL_ARMS,
+                consts::ENEMY_SUPER_SPIRAL_TURN,
+            ),
+            SuperPattern::Burst => BulletPattern::AimedBurst(
+                consts::ENEMY_SUPER_BURST_COUNT,
+                consts::ENEMY_SUPER_BURST_SPREAD,
+            ),
+            SuperPattern::Wall => {
+                BulletPattern::Wall(consts::ENEMY_SUPER_WALL_COUNT, consts::ENEMY_SUPER_WALL_GAP)
+            }
+        }
+    }
+
+    fn reload_time(&self) -> f32 {
+        match self {
+            SuperPattern::Spiral => consts::ENEMY_SUPER_SPIRAL_RELOAD_TIME,
+            SuperPattern::Burst => consts::ENEMY_SUPER_BURST_RELOAD_TIME,
+            SuperPattern::Wall => consts::ENEMY_SUPER_WALL_RELOAD_TIME,
+        }
+    }
 }
 
 enum EnemyMovementType {
     Chase,
     Predict(f32), // lead time
+    March(f32),   // direction, ignores the player
+}
+
+// groups of enemies spawned together, one kind is picked per wave
+#[derive(Clone, Copy)]
+pub enum Formation {
+    PawnWall,    // line of pawns marching straight ahead
+    Ring,        // pawns closing in from all sides
+    BishopSquad, // bishops side by side
+    Swarm,       // weak and fast pawns bunched up
+}
+
+impl Formation {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pawn_wall" => Some(Formation::PawnWall),
+            "ring" => Some(Formation::Ring),
+            "bishop_squad" => Some(Formation::BishopSquad),
+            "swarm" => Some(Formation::Swarm),
+            _ => None,
+        }
+    }
+
+    fn random(wave: i32) -> Option<Self> {
+        let mut formations = Vec::new();
+        if wave >= consts::ENEMY_FORMATION_WAVE_START {
+            formations.push(Formation::PawnWall);
+            formations.push(Formation::Ring);
+        }
+        if wave >= consts::ENEMY_SWARM_WAVE_START {
+            formations.push(Formation::Swarm);
+        }
+        if wave >= consts::ENEMY_SQUAD_WAVE_START {
+            formations.push(Formation::BishopSquad);
+        }
+        formations.choose().copied()
+    }
+}
+
+// rooks line up with the player, telegraph a lane, then dash down it
+enum EnemyCharge {
+    LineUp,
+    Telegraph(mq::Vec2),       // direction
+    Dash(mq::Vec2, f32, bool), // direction, distance left, hit player
+}
+
+// knights hop in L shapes, showing where they will land first
+enum EnemyHop {
+    Wait,
+    Telegraph(mq::Vec2),                     // landing pos
+    Jump(mq::Vec2, mq::Vec2, mq::Vec2, f32), // start pos, corner pos, landing pos, time in air
+}
+
+impl EnemyHop {
+    fn pick_landing(pos: mq::Vec2, target: mq::Vec2) -> mq::Vec2 {
+        // the 8 knight moves, closest to the target
+        let short = consts::ENEMY_KNIGHT_HOP_SIZE;
+        let long = 2.0 * short;
+        [
+            (short, long),
+            (long, short),
+            (long, -short),
+            (short, -long),
+            (-short, -long),
+            (-long, -short),
+            (-long, short),
+            (-short, long),
+        ]
+        .into_iter()
+        .map(|(x, y)| pos + mq::Vec2::new(x, y))
+        .min_by(|a, b| {
+            a.distance_squared(target)
+                .partial_cmp(&b.distance_squared(target))
+                .unwrap()
+        })
+        .unwrap()
+    }
+
+    fn corner(start: mq::Vec2, landing: mq::Vec2) -> mq::Vec2 {
+        // move along the long leg first
+        let offset = landing - start;
+        if offset.x.abs() > offset.y.abs() {
+            mq::Vec2::new(landing.x, start.y)
+        } else {
+            mq::Vec2::new(start.x, landing.y)
+        }
+    }
 }
 
 pub struct Enemy {
@@ -114,8 +330,17 @@ pub struct Enemy {
     direction: f32, // radians
     pub enemy_type: EnemyType,
     enemy_attack: EnemyAttack,
-    pub enemy_stunned: EnemyStunned,
+    pub status_effects: status_effect::StatusEffects,
     enemy_movement: EnemyMovementType,
+    enemy_charge: EnemyCharge,
+    trampled: Vec<usize>, // ids of the enemies run over during the current dash
+    enemy_hop: EnemyHop,
+    flocking: bool,    // steers with nearby flocking enemies
+    volley: u32,       // bullet pattern volleys fired
+    pattern_time: f32, // seconds spent shooting patterns
+    pub affixes: Vec<affix::Affix>,
+    shield_hits: u32,   // hits left to block
+    reflects_left: u32, // bullets left to bounce back
     pub id: usize,
 }
 
@@ -149,23 +374,151 @@ impl Enemy {
             direction: 0.0,
             enemy_type,
             enemy_attack: EnemyAttack::new(),
-            enemy_stunned: EnemyStunned::new(),
+            status_effects: status_effect::StatusEffects::new(),
             enemy_movement,
+            enemy_charge: EnemyCharge::LineUp,
+            trampled: Vec::new(),
+            enemy_hop: EnemyHop::Wait,
+            flocking: false,
+            volley: 0,
+            pattern_time: 0.0,
+            affixes: Vec::new(),
+            shield_hits: 0,
+            reflects_left: 0,
             id,
         }
     }
 
-    fn update(&mut self, player: &mut player::Player, wave: i32, max_dist: f32, delta: f32) -> (EnemyShotType, Option<damage_number::DamageNumber>) {
-        self.enemy_stunned.update(delta);
+    fn with_affixes(mut self, affixes: Vec<affix::Affix>) -> Self {
+        for affix in affixes.iter() {
+            match affix {
+                affix::Affix::Shielded => self.shield_hits = consts::ENEMY_SHIELDED_HITS,
+                affix::Affix::Swift => self.speed *= consts::ENEMY_SWIFT_SPEED_MOD,
+                affix::Affix::Reflective => self.reflects_left = consts::ENEMY_REFLECTIVE_BULLETS,
+                affix::Affix::Splitting | affix::Affix::Vampiric => {}
+            }
+        }
+        self.affixes = affixes;
+        self
+    }
+
+    fn has_affix(&self, affix: affix::Affix) -> bool {
+        self.affixes.contains(&affix)
+    }
+
+    // returns true if the hit was blocked by a shield
+    pub fn block_hit(&mut self) -> bool {
+        if self.shield_hits > 0 {
+            self.shield_hits -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    // returns the damage of the bounced back bullet
+    pub fn try_reflect(&mut self) -> Option<f32> {
+        if self.reflects_left > 0 {
+            self.reflects_left -= 1;
+            Some(self.damage)
+        } else {
+            None
+        }
+    }
+
+    fn drops(&self) -> Vec<pickup::Pickup> {
+        let scatter = || {
+            let angle = mq::rand::gen_range(0.0, 2.0 * std::f32::consts::PI);
+            self.pos + mq::Vec2::new(angle.cos(), angle.sin()) * consts::PICKUP_SCATTER
+        };
+
+        // elites drop bigger gems
+        let xp = self.enemy_type.xp() + consts::ENEMY_ELITE_XP * self.affixes.len() as i32;
+        let mut drops = vec![pickup::Pickup::new(self.pos, pickup::PickupType::Xp(xp))];
+
+        if mq::rand::gen_range(0.0, 1.0) < consts::PICKUP_HEALTH_CHANCE {
+            drops.push(pickup::Pickup::new(
+                scatter(),
+                pickup::PickupType::Health(consts::PICKUP_HEALTH_AMOUNT),
+            ));
+        }
+        if mq::rand::gen_range(0.0, 1.0) < consts::PICKUP_CHIP_CHANCE {
+            drops.push(pickup::Pickup::new(
+                scatter(),
+                pickup::PickupType::Chip(consts::PICKUP_CHIP_SCORE),
+            ));
+        }
+        // queens and kings already give card buffs
+        if !matches!(self.enemy_type, EnemyType::Super | EnemyType::King)
+            && mq::rand::gen_range(0.0, 1.0) < consts::PICKUP_CARD_CHANCE
+        {
+            drops.push(pickup::Pickup::new(scatter(), pickup::PickupType::Card));
+        }
+        drops
+    }
+
+    fn hit_player(
+        &mut self,
+        player: &mut player::Player,
+        damage: f32,
+    ) -> Option<damage_number::DamageNumber> {
+        if player.is_invulnerable() {
+            return None;
+        }
+        let damage = player.take_hit(damage, self.pos);
+        if self.has_affix(affix::Affix::Vampiric) {
+            self.health =
+                (self.health + self.max_health * consts::ENEMY_VAMPIRIC_HEAL).min(self.max_health);
+        }
+        Some(damage_number::DamageNumber::new(
+            format!("-{}", (damage * 10.0).round() / 10.0),
+            consts::DAMAGE_NUMBER_TIME,
+            player.pos,
+            damage_number::DamageNumberColor::PlayerDamage,
+        ))
+    }
+
+    fn with_movement(mut self, enemy_movement: EnemyMovementType) -> Self {
+        self.enemy_movement = enemy_movement;
+        self
+    }
+
+    // moved somewhere else, keeping its health, type and affixes
+    fn recycle(&mut self, pos: mq::Vec2) {
+        self.pos = pos;
+        self.enemy_attack = EnemyAttack::new();
+        self.enemy_charge = EnemyCharge::LineUp;
+        self.enemy_hop = EnemyHop::Wait;
+        // marching in a straight line would likely miss the player now
+        if let EnemyMovementType::March(_) = self.enemy_movement {
+            self.enemy_movement = EnemyMovementType::Chase;
+        }
+    }
+
+    fn boss_phase(&self) -> BossPhase {
+        BossPhase::from_health_ratio(self.health / self.max_health)
+    }
+
+    fn update(
+        &mut self,
+        player: &mut player::Player,
+        wave: i32,
+        delta: f32,
+    ) -> (Option<BulletPattern>, Option<damage_number::DamageNumber>) {
+        self.health -= self.status_effects.update(delta);
+        self.pos += self.status_effects.knockback_movement(delta);
         let mut damage_number = None;
-        if self.enemy_stunned.is_stunned() {
-            return (EnemyShotType::None, damage_number);
+        if self.status_effects.is_stunned() {
+            return (None, damage_number);
         }
 
         let player_target_pos = match self.enemy_movement {
             EnemyMovementType::Chase => player.pos,
             EnemyMovementType::Predict(lead_time) => {
-                player.pos + player.movement * consts::PLAYER_SPEED * lead_time
+                player.pos + player.movement * player.stats.move_speed() * lead_time
+            }
+            EnemyMovementType::March(direction) => {
+                self.pos + mq::Vec2::new(direction.cos(), direction.sin())
             }
         };
 
@@ -173,25 +526,20 @@ impl Enemy {
         let vec_to_player = player.pos - self.pos;
         let distance_to_player = vec_to_player.length();
 
-
-        let max_wrap = consts::ENEMY_MAX_RANGE_MULT * max_dist;
-        if distance_to_player > max_wrap {
-            let wrap = vec_to_player.normalize_or_zero() * consts::ENEMY_WRAP_STRENGH * max_wrap;
-            self.pos += wrap;
-            return (EnemyShotType::None, damage_number);
-        }
-        
         self.direction = vec_to_target.y.atan2(vec_to_target.x);
         let mut movement =
             mq::Vec2::new(self.direction.cos(), self.direction.sin()) * self.speed * delta;
 
-        let mut enemy_shot_type = EnemyShotType::None;
+        let mut bullet_pattern = None;
 
         let range = self.enemy_type.range();
         let charge_time = self.enemy_type.charge_time();
         let reload_time = self.enemy_type.reload_time();
 
         self.enemy_attack.update(delta);
+        if self.enemy_type == EnemyType::Super && distance_to_player < range {
+            self.pattern_time += delta;
+        }
 
         if self.enemy_attack.time_until_next_attack > 0.0 {
             self.enemy_attack.time_until_next_attack -= delta;
@@ -205,7 +553,7 @@ impl Enemy {
                             self.enemy_attack.time_until_next_attack = reload_time;
                             self.enemy_attack.time_in_range = 0.0;
 
-                            enemy_shot_type = EnemyShotType::Standard;
+                            bullet_pattern = Some(BulletPattern::AimedBurst(1, 0.0));
                         }
                     } else if distance_to_player < consts::ENEMY_RANGED_RANGE {
                         self.enemy_attack.time_in_range += delta;
@@ -218,13 +566,7 @@ impl Enemy {
                             self.enemy_attack.time_until_next_attack = reload_time;
                             self.enemy_attack.time_in_range = 0.0;
 
-                            player.health -= self.damage;
-                            damage_number = Some(damage_number::DamageNumber::new(
-                                format!("-{}", self.damage).to_owned(),
-                                consts::DAMAGE_NUMBER_TIME,
-                                player.pos,
-                                damage_number::DamageNumberColor::PlayerDamage
-                            ));
+                            damage_number = self.hit_player(player, self.damage);
                         }
                     } else {
                         self.enemy_attack.time_in_range = 0.0;
@@ -233,15 +575,167 @@ impl Enemy {
                 EnemyType::Super => {
                     if distance_to_player < range && self.enemy_attack.time_until_next_attack <= 0.0
                     {
-                        self.enemy_attack.time_until_next_attack =
-                            1.0 / consts::ENEMY_SUPER_WAVE_FIRE_RATE(wave);
+                        let super_pattern = SuperPattern::from_time(self.pattern_time);
+                        self.enemy_attack.time_until_next_attack = super_pattern.reload_time()
+                            / consts::ENEMY_SUPER_WAVE_FIRE_RATE_MOD(wave);
 
-                        enemy_shot_type = EnemyShotType::Spread;
+                        bullet_pattern = Some(super_pattern.pattern());
+                    }
+                }
+                EnemyType::Rook => {
+                    if let EnemyCharge::LineUp = self.enemy_charge {
+                        let offset = vec_to_player.abs();
+                        if offset.min_element() < consts::ENEMY_ROOK_ALIGN_TOLERANCE
+                            && offset.max_element() < range
+                        {
+                            // charge along the axis the player is lined up on
+                            let direction = if offset.x > offset.y {
+                                mq::Vec2::new(vec_to_player.x.signum(), 0.0)
+                            } else {
+                                mq::Vec2::new(0.0, vec_to_player.y.signum())
+                            };
+                            self.enemy_charge = EnemyCharge::Telegraph(direction);
+                            self.enemy_attack.time_in_range = 0.0;
+                        }
+                    }
+                }
+                EnemyType::King => {
+                    if distance_to_player < range {
+                        let boss_phase = self.boss_phase();
+                        self.enemy_attack.time_until_next_attack = boss_phase.reload_time();
+
+                        bullet_pattern = Some(boss_phase.pattern(self.volley));
+                    }
+                }
+                EnemyType::Knight => {
+                    if let EnemyHop::Wait = self.enemy_hop {
+                        let landing = EnemyHop::pick_landing(self.pos, player.pos);
+                        self.enemy_hop = EnemyHop::Telegraph(landing);
+                        self.enemy_attack.time_in_range = 0.0;
                     }
                 }
             };
         }
 
+        if self.enemy_type == EnemyType::Knight {
+            movement = mq::Vec2::ZERO;
+            match self.enemy_hop {
+                EnemyHop::Wait => {}
+                EnemyHop::Telegraph(landing) => {
+                    self.enemy_attack.time_in_range += delta;
+                    if self.enemy_attack.time_in_range >= charge_time {
+                        self.enemy_attack.time_in_range = 0.0;
+                        let corner = EnemyHop::corner(self.pos, landing);
+                        self.enemy_hop = EnemyHop::Jump(self.pos, corner, landing, 0.0);
+                    }
+                }
+                EnemyHop::Jump(start, corner, landing, time) => {
+                    let time = time + delta;
+                    let ratio = (time / consts::ENEMY_KNIGHT_HOP_TIME).min(1.0);
+
+                    // split the hop between the two legs by their length
+                    let long_leg = start.distance(corner);
+                    let leg_ratio = long_leg / (long_leg + corner.distance(landing));
+                    let new_pos = if ratio < leg_ratio {
+                        start.lerp(corner, ratio / leg_ratio)
+                    } else {
+                        corner.lerp(landing, (ratio - leg_ratio) / (1.0 - leg_ratio))
+                    };
+                    let hop_movement = new_pos - self.pos;
+                    self.direction = hop_movement.y.atan2(hop_movement.x);
+                    self.pos = new_pos;
+
+                    self.enemy_hop = if ratio >= 1.0 {
+                        self.enemy_attack.time_until_next_attack = reload_time;
+
+                        if player.pos.distance(landing) < range + player.radius() {
+                            let damage = self.damage * consts::ENEMY_KNIGHT_DAMAGE_MOD;
+                            damage_number = self.hit_player(player, damage);
+                        }
+                        EnemyHop::Wait
+                    } else {
+                        EnemyHop::Jump(start, corner, landing, time)
+                    };
+                }
+            }
+        }
+
+        if self.enemy_type == EnemyType::Rook {
+            match self.enemy_charge {
+                EnemyCharge::LineUp => {
+                    // move to share a row or column with the player, closing in if too far away
+                    let offset = vec_to_player.abs();
+                    let target = if offset.x < offset.y {
+                        mq::Vec2::new(
+                            player.pos.x,
+                            if offset.y > range {
+                                player.pos.y
+                            } else {
+                                self.pos.y
+                            },
+                        )
+                    } else {
+                        mq::Vec2::new(
+                            if offset.x > range {
+                                player.pos.x
+                            } else {
+                                self.pos.x
+                            },
+                            player.pos.y,
+                        )
+                    };
+                    let vec_to_target = target - self.pos;
+                    let step = (self.speed * delta).min(vec_to_target.length());
+                    movement = vec_to_target.normalize_or_zero() * step;
+                }
+                EnemyCharge::Telegraph(direction) => {
+                    movement = mq::Vec2::ZERO;
+                    self.direction = direction.y.atan2(direction.x);
+                    self.enemy_attack.time_in_range += delta;
+                    if self.enemy_attack.time_in_range >= charge_time {
+                        self.enemy_attack.time_in_range = 0.0;
+                        self.trampled.clear();
+                        self.enemy_charge =
+                            EnemyCharge::Dash(direction, consts::ENEMY_ROOK_DASH_DISTANCE, false);
+                    }
+                }
+                EnemyCharge::Dash(direction, distance_left, hit_player) => {
+                    // moved here so slows shorten each step, not the whole dash
+                    let step =
+                        (consts::ENEMY_ROOK_DASH_SPEED * self.status_effects.speed_mod() * delta)
+                            .min(distance_left);
+                    movement = mq::Vec2::ZERO;
+                    self.pos += direction * step;
+                    self.direction = direction.y.atan2(direction.x);
+
+                    let mut hit_player = hit_player;
+                    if !hit_player && hitbox::circles_collide(self, player) {
+                        hit_player = true;
+
+                        let damage = self.damage * consts::ENEMY_ROOK_DAMAGE_MOD;
+                        damage_number = self.hit_player(player, damage);
+                        // getting run over leaves the player dazed
+                        if damage_number.is_some() {
+                            player
+                                .status_effects
+                                .apply(status_effect::StatusEffect::Slow(
+                                    consts::ENEMY_ROOK_SLOW_TIME,
+                                    consts::ENEMY_ROOK_SLOW_SPEED_MOD,
+                                ));
+                        }
+                    }
+
+                    let distance_left = distance_left - step;
+                    self.enemy_charge = if distance_left <= 0.0 {
+                        self.enemy_attack.time_until_next_attack = reload_time;
+                        EnemyCharge::LineUp
+                    } else {
+                        EnemyCharge::Dash(direction, distance_left, hit_player)
+                    };
+                }
+            }
+        }
+
         match self.enemy_type {
             EnemyType::Ranged => {
                 if distance_to_player < consts::ENEMY_RANGED_RANGE {
@@ -255,15 +749,22 @@ impl Enemy {
                     movement = mq::Vec2::ZERO;
                 }
             }
+            EnemyType::King => {
+                if distance_to_player < consts::ENEMY_BOSS_MIN_RANGE {
+                    movement = mq::Vec2::ZERO;
+                } else {
+                    movement *= self.boss_phase().speed_mod();
+                }
+            }
             _ => {}
         }
 
-        self.pos += movement;
+        self.pos += movement * self.status_effects.speed_mod();
 
-        (enemy_shot_type, damage_number)
+        (bullet_pattern, damage_number)
     }
 
-    pub fn draw_hp_bar(&self, camera: &camera::Camera, scale: f32) {
+    pub fn draw_hp_bar(&self, camera: &camera::Camera, font: &mq::Font, scale: f32) {
         let draw_pos = (self.pos - camera.pos) * scale / consts::TILES_PER_SCALE as f32
             + mq::Vec2::new(mq::screen_width() / 2.0, mq::screen_height() / 2.0);
         let square_radius = scale * self.enemy_type.size();
@@ -279,6 +780,32 @@ impl Enemy {
             hp_bar_height,
             colors::NORD14,
         );
+
+        // affix names stacked above the hp bar
+        let font_size = (scale * consts::ENEMY_ELITE_FONT_SIZE).round() as u16;
+        let mut text_y = draw_pos.y - square_radius * 0.75 - hp_bar_height * 2.0;
+        for affix in self.affixes.iter().rev() {
+            let text = match affix {
+                affix::Affix::Shielded if self.shield_hits > 0 => {
+                    format!("{} ({})", affix.name(), self.shield_hits)
+                }
+                _ => affix.name().to_owned(),
+            };
+            let text_dims = mq::measure_text(&text, Some(font), font_size, 1.0);
+            mq::draw_text_ex(
+                &text,
+                draw_pos.x - text_dims.width / 2.0,
+                text_y,
+                mq::TextParams {
+                    font: Some(font),
+                    font_size,
+                    font_scale: 1.0,
+                    color: affix.color(),
+                    ..mq::TextParams::default()
+                },
+            );
+            text_y -= text_dims.height * 1.25;
+        }
     }
 
     pub fn draw(&self, camera: &camera::Camera, chess_texture: &mq::Texture2D, scale: f32) {
@@ -304,10 +831,65 @@ impl Enemy {
             }
         }
 
+        // charge lane indicator
+        if let EnemyCharge::Telegraph(direction) = self.enemy_charge {
+            let tile_size = scale / consts::TILES_PER_SCALE as f32;
+            let lane_length = consts::ENEMY_ROOK_DASH_DISTANCE * tile_size;
+            let lane_width = self.radius() * 2.0 * tile_size;
+            let charge_ratio = (self.enemy_attack.time_in_range
+                / consts::ENEMY_ROOK_TELEGRAPH_TIME)
+                .clamp(0.0, 1.0);
+
+            let lane_end = draw_pos + direction * lane_length;
+            let filled_end = draw_pos + direction * lane_length * charge_ratio;
+            let lane_thickness = mq::Vec2::new(direction.y.abs(), direction.x.abs()) * lane_width;
+
+            let lane_rect = |end: mq::Vec2| {
+                let min = draw_pos.min(end) - lane_thickness / 2.0;
+                let max = draw_pos.max(end) + lane_thickness / 2.0;
+                mq::Rect::new(min.x, min.y, max.x - min.x, max.y - min.y)
+            };
+            let lane = lane_rect(lane_end);
+            let filled = lane_rect(filled_end);
+
+            mq::draw_rectangle(lane.x, lane.y, lane.w, lane.h, colors::NORD11_BIG_ALPHA);
+            mq::draw_rectangle(filled.x, filled.y, filled.w, filled.h, colors::NORD11_ALPHA);
+        }
+
+        // landing square indicator
+        if let EnemyHop::Telegraph(landing) = self.enemy_hop {
+            let landing_pos = (landing - camera.pos) * scale / consts::TILES_PER_SCALE as f32
+                + mq::Vec2::new(mq::screen_width() / 2.0, mq::screen_height() / 2.0);
+            let size =
+                2.0 * consts::ENEMY_KNIGHT_LANDING_RADIUS * scale / consts::TILES_PER_SCALE as f32;
+            let charge_ratio = (self.enemy_attack.time_in_range
+                / consts::ENEMY_KNIGHT_TELEGRAPH_TIME)
+                .clamp(0.0, 1.0);
+            let filled_size = size * charge_ratio;
+
+            mq::draw_rectangle(
+                landing_pos.x - size / 2.0,
+                landing_pos.y - size / 2.0,
+                size,
+                size,
+                colors::NORD11_BIG_ALPHA,
+            );
+            mq::draw_rectangle(
+                landing_pos.x - filled_size / 2.0,
+                landing_pos.y - filled_size / 2.0,
+                filled_size,
+                filled_size,
+                colors::NORD11_ALPHA,
+            );
+        }
+
         let texture_info = consts::CHESS_TEXTURE_INFO[match self.enemy_type {
             EnemyType::Melee => consts::CHESS_PAWN_INDEX,
             EnemyType::Ranged => consts::CHESS_BISHOP_INDEX,
             EnemyType::Super => consts::CHESS_QUEEN_INDEX,
+            EnemyType::Rook => consts::CHESS_ROOK_INDEX,
+            EnemyType::Knight => consts::CHESS_KNIGHT_INDEX,
+            EnemyType::King => consts::CHESS_ENEMY_KING_INDEX,
         }];
         let texture_source = mq::Rect::new(
             texture_info.0 as f32,
@@ -318,6 +900,20 @@ impl Enemy {
 
         let square_radius = scale * self.enemy_type.size();
 
+        // one outline per affix, nested inwards
+        let outline_thickness = consts::ENEMY_ELITE_OUTLINE_THICKNESS * scale;
+        for (i, affix) in self.affixes.iter().enumerate() {
+            let inset = i as f32 * outline_thickness;
+            mq::draw_rectangle_lines(
+                draw_pos.x - square_radius / 2.0 + inset,
+                draw_pos.y - square_radius / 2.0 + inset,
+                square_radius - 2.0 * inset,
+                square_radius - 2.0 * inset,
+                outline_thickness,
+                affix.color(),
+            );
+        }
+
         mq::draw_texture_ex(
             chess_texture,
             draw_pos.x - square_radius / 2.0,
@@ -344,25 +940,7 @@ impl Enemy {
         //     },
         // );
 
-        if self.enemy_stunned.is_stunned() {
-            // mq::draw_poly_lines(
-            //     draw_pos.x,
-            //     draw_pos.y,
-            //     4,
-            //     square_radius,
-            //     util::rad_to_deg(self.direction + std::f32::consts::PI / 4.0), // rotate 45 degrees
-            //     consts::ENEMY_STUNNED_THICKNESS * scale,
-            //     colors::NORD10,
-            // );
-            mq::draw_rectangle_lines(
-                draw_pos.x - square_radius / 2.0,
-                draw_pos.y - square_radius / 2.0,
-                square_radius,
-                square_radius,
-                consts::ENEMY_STUNNED_THICKNESS * scale,
-                colors::NORD10,
-            );
-        }
+        self.status_effects.draw(draw_pos, square_radius, scale);
     }
 }
 
@@ -372,17 +950,16 @@ impl hitbox::Circle for Enemy {
     }
 
     fn radius(&self) -> f32 {
-        match self.enemy_type {
-            EnemyType::Super => consts::ENEMY_SUPER_SIZE * consts::TILES_PER_SCALE as f32,
-            _ => consts::ENEMY_SIZE * consts::TILES_PER_SCALE as f32,
-        }
+        self.enemy_type.size() * consts::TILES_PER_SCALE as f32
     }
 
 }
 
 pub struct EnemiesKilled {
     pub count: i32,
+    pub drops: Vec<pickup::Pickup>,
     pub super_killed: bool,
+    pub boss_killed: bool,
 }
 
 pub struct EnemyManager {
@@ -392,23 +969,49 @@ pub struct EnemyManager {
     spawn_timer: timer::Timer<()>,
     enemy_bullets: Vec<bullet::Bullet>,
     should_spawn_super: bool,
+    super_spawned: bool, // this wave
+    wave_formation: Option<Formation>,
     next_enemy_id: usize,
+    boss_arena: Option<mq::Vec2>, // center of the arena while a boss is alive
+    wave_config: wave_config::WaveConfig,
+    wave_time: f32,                               // seconds since the wave started
+    wave_events: Vec<wave_config::WaveEvent>,     // not yet due this wave, in time order
+    due_events: VecDeque<wave_config::WaveEvent>, // waiting for room under the cap, in time order
+    view: mq::Rect,                               // visible area in tiles, from the last update
+    spawn_telegraphs: Vec<(Enemy, f32)>,          // (enemy, time until it spawns)
 }
 
 impl EnemyManager {
-    pub fn new() -> Self {
+    pub fn new(wave_config: &wave_config::WaveConfig) -> Self {
         Self {
             enemies: Vec::new(),
             wave: 0,
             enemies_until_next_wave: 0,
-            spawn_timer: timer::Timer::new(1.0 / consts::ENEMY_WAVE_SPAWN_RATE(0)),
+            spawn_timer: timer::Timer::new(1.0 / wave_config.spawn_rate(0)),
             enemy_bullets: Vec::new(),
             should_spawn_super: false,
+            super_spawned: false,
+            wave_formation: None,
             next_enemy_id: 0,
+            boss_arena: None,
+            wave_config: wave_config.clone(),
+            wave_time: 0.0,
+            wave_events: Vec::new(),
+            due_events: VecDeque::new(),
+            view: mq::Rect::new(0.0, 0.0, 0.0, 0.0),
+            spawn_telegraphs: Vec::new(),
         }
     }
 
-    pub fn update(&mut self, player: &mut player::Player, max_dist: f32, delta: f32) -> (EnemiesKilled, Vec<damage_number::DamageNumber>) {
+    pub fn update(
+        &mut self,
+        player: &mut player::Player,
+        view: mq::Rect,
+        delta: f32,
+    ) -> (EnemiesKilled, Vec<damage_number::DamageNumber>) {
+        self.view = view;
+        let max_dist = view.size().length();
+
         let previous_enemy_count = self.enemies.len() as i32;
         let super_count = self
             .enemies
@@ -416,68 +1019,89 @@ impl EnemyManager {
             .filter(|enemy| enemy.enemy_type == EnemyType::Super)
             .count();
 
+        let dead_enemies = self.enemies.iter().filter(|enemy| enemy.health <= 0.0);
+        let drops = dead_enemies.clone().flat_map(Enemy::drops).collect();
+        let split_positions = dead_enemies
+            .filter(|enemy| enemy.has_affix(affix::Affix::Splitting))
+            .map(|enemy| enemy.pos)
+            .collect::<Vec<_>>();
+
         self.enemies.retain(|enemy| enemy.health > 0.0);
 
         let count = previous_enemy_count - self.enemies.len() as i32;
+
+        // splits don't count towards the wave
+        for pos in split_positions {
+            self.spawn_splits(pos);
+        }
         let super_killed = super_count
             > self
                 .enemies
                 .iter()
                 .filter(|enemy| enemy.enemy_type == EnemyType::Super)
                 .count();
+        let boss_killed = self.boss_arena.is_some()
+            && !self
+                .enemies
+                .iter()
+                .any(|enemy| enemy.enemy_type == EnemyType::King);
+        if boss_killed {
+            self.boss_arena = None;
+        }
 
         let mut damage_numbers = Vec::new();
 
         for enemy in self.enemies.iter_mut() {
-            let (enemy_shot_type, damage_number) = enemy.update(player, self.wave, max_dist, delta);
+            let (bullet_pattern, damage_number) = enemy.update(player, self.wave, delta);
 
             if let Some(damage_number) = damage_number {
                 damage_numbers.push(damage_number);
             }
 
-            match enemy_shot_type {
-                EnemyShotType::None => {}
-                EnemyShotType::Standard => {
-                    let bullet = bullet::Bullet::new(
-                        enemy.pos,
-                        enemy.direction,
-                        consts::ENEMY_RANGED_BULLET_SPEED,
-                        consts::ENEMY_RANGED_BULLET_RANGE,
-                        bullet::BulletDamage::Standard(enemy.damage),
-                        1,
-                    );
-                    self.enemy_bullets.push(bullet);
-                }
-                EnemyShotType::Spread => {
-                    let spread = consts::ENEMY_SUPER_SPREAD;
-                    let angle = enemy.direction + mq::rand::gen_range(-spread, spread);
-                    let bullet = bullet::Bullet::new(
-                        enemy.pos,
-                        angle,
-                        consts::ENEMY_RANGED_BULLET_SPEED,
-                        consts::ENEMY_RANGED_BULLET_RANGE,
-                        bullet::BulletDamage::Standard(enemy.damage),
-                        1,
-                    );
-                    self.enemy_bullets.push(bullet);
-                }
+            if let Some(bullet_pattern) = bullet_pattern {
+                self.enemy_bullets.extend(bullet_pattern.fire(
+                    enemy.pos,
+                    enemy.direction,
+                    enemy.volley,
+                    enemy.damage,
+                ));
+                enemy.volley += 1;
             }
         }
 
+        self.steer_around_neighbors(delta);
+
+        // no recycling during boss fights, everything stays in the arena
+        if self.boss_arena.is_none() {
+            self.recycle_stragglers(player, max_dist);
+        }
+
+        // keep the player inside the arena
+        if let Some(center) = self.boss_arena {
+            let offset = player.pos - center;
+            if offset.length() > consts::ENEMY_BOSS_ARENA_RADIUS {
+                player.pos = center + offset.normalize() * consts::ENEMY_BOSS_ARENA_RADIUS;
+            }
+        }
+
+        damage_numbers.extend(self.trample_lanes());
+
         self.enemy_bullets
             .iter_mut()
             .for_each(|bullet| bullet.update(delta));
 
         for bullet in self.enemy_bullets.iter_mut() {
-            if hitbox::circles_collide(bullet, player) {
+            // bullets pass through while invulnerable
+            if hitbox::circles_collide(bullet, player) && !player.is_invulnerable() {
                 let damage = match bullet.bullet_damage {
                     bullet::BulletDamage::Standard(damage) => damage,
-                    bullet::BulletDamage::Card(card) => card.damage(None),
+                    bullet::BulletDamage::Card(card) => card.damage(),
+                    bullet::BulletDamage::Wild(card, damage_mod) => card.damage() * damage_mod,
                 };
-                player.health -= damage;
+                let damage = player.take_hit(damage, bullet.center());
 
                 damage_numbers.push(damage_number::DamageNumber::new(
-                    format!("-{}", damage).to_owned(),
+                    format!("-{}", (damage * 10.0).round() / 10.0),
                     consts::DAMAGE_NUMBER_TIME,
                     player.pos,
                     damage_number::DamageNumberColor::PlayerDamage
@@ -488,63 +1112,491 @@ impl EnemyManager {
 
         self.enemy_bullets.retain(bullet::Bullet::should_keep);
 
+        if self.boss_arena.is_none() {
+            self.wave_time += delta;
+            let due_count = self
+                .wave_events
+                .iter()
+                .take_while(|event| event.time <= self.wave_time)
+                .count();
+            self.due_events.extend(self.wave_events.drain(..due_count));
+
+            // due events wait in line until there is room under the cap
+            while self.room() > 0 {
+                let Some(event) = self.due_events.pop_front() else {
+                    break;
+                };
+                self.spawn_wave_event(event);
+            }
+        }
+
         if let util::Ticked(true) = self.spawn_timer.update(delta) {
-            if self.enemies_until_next_wave <= 0 {
+            if self.boss_arena.is_some() {
+                // no regular spawns during boss fights
+            } else if self.room() == 0 {
+                // wait for room under the cap
+            } else if self.enemies_until_next_wave <= 0 {
                 self.wave += 1;
-                self.enemies_until_next_wave = consts::ENEMY_WAVE_COUNT(self.wave);
+                self.enemies_until_next_wave = self.wave_config.count(self.wave);
                 self.spawn_timer
-                    .update_period(1.0 / consts::ENEMY_WAVE_SPAWN_RATE(self.wave));
-            } else if self.enemies_until_next_wave == consts::ENEMY_WAVE_COUNT(self.wave) / 2
-                && self.wave > consts::ENEMY_SUPER_WAVE_START
-            {
-                self.should_spawn_super = true;
+                    .update_period(1.0 / self.wave_config.spawn_rate(self.wave));
+                self.super_spawned = false;
+                self.wave_formation = match self.wave_config.formations(self.wave) {
+                    Some(formations) => formations.choose().copied(),
+                    None => Formation::random(self.wave),
+                };
+                // events the last wave ended too early for happen right away
+                self.due_events.extend(self.wave_events.drain(..));
+                self.wave_time = 0.0;
+                self.wave_events = self
+                    .wave_config
+                    .events(self.wave)
+                    .map(<[_]>::to_vec)
+                    .unwrap_or_default();
+                self.wave_events
+                    .sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
+
+                if self.wave % consts::ENEMY_BOSS_WAVE_PERIOD == 0 {
+                    self.spawn_boss(player);
+                } else {
+                    self.spawn_enemy();
+                }
+            } else {
+                // formations can skip past the exact halfway point
+                if self.enemies_until_next_wave <= self.wave_config.count(self.wave) / 2
+                    && !self.super_spawned
+                    && self.wave_config.spawns_super(self.wave)
+                    && self.wave % consts::ENEMY_BOSS_WAVE_PERIOD != 0
+                {
+                    self.should_spawn_super = true;
+                    self.super_spawned = true;
+                }
+
+                match self.wave_formation {
+                    Some(formation)
+                        if !self.should_spawn_super
+                            && mq::rand::gen_range(0.0, 1.0)
+                                < self.wave_config.formation_chance(self.wave) =>
+                    {
+                        self.spawn_formation(formation);
+                    }
+                    _ => self.spawn_enemy(),
+                }
+            }
+        }
+
+        // telegraphed enemies appear once their time is up
+        for (_, time_left) in self.spawn_telegraphs.iter_mut() {
+            *time_left -= delta;
+        }
+        let (spawned, waiting) = std::mem::take(&mut self.spawn_telegraphs)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(_, time_left)| *time_left <= 0.0);
+        self.spawn_telegraphs = waiting;
+        self.enemies
+            .extend(spawned.into_iter().map(|(enemy, _)| enemy));
+
+        (
+            EnemiesKilled {
+                count,
+                drops,
+                super_killed,
+                boss_killed,
+            },
+            damage_numbers,
+        )
+    }
+
+    // dashing rooks run over the other enemies in their lane, shoving them aside
+    fn trample_lanes(&mut self) -> Vec<damage_number::DamageNumber> {
+        let mut damage_numbers = Vec::new();
+        for i in 0..self.enemies.len() {
+            let EnemyCharge::Dash(direction, _, _) = self.enemies[i].enemy_charge else {
+                continue;
+            };
+            for j in 0..self.enemies.len() {
+                let (rook, other) = (&self.enemies[i], &self.enemies[j]);
+                // queens and kings are too heavy to run over
+                if i == j
+                    || matches!(other.enemy_type, EnemyType::Super | EnemyType::King)
+                    || rook.trampled.contains(&other.id)
+                    || !hitbox::circles_collide(rook, other)
+                {
+                    continue;
+                }
+                let rook_pos = rook.pos;
+                let other_id = other.id;
+                self.enemies[i].trampled.push(other_id);
+
+                let other = &mut self.enemies[j];
+                let damage = other.max_health
+                    * consts::ENEMY_ROOK_TRAMPLE_DAMAGE
+                    * other.status_effects.damage_taken_mod();
+                other.health -= damage;
+
+                // out of the lane, to whichever side it was already on
+                let offset = other.pos - rook_pos;
+                let side = offset - direction * offset.dot(direction);
+                let away = if side.length() > 0.0 {
+                    side.normalize()
+                } else {
+                    direction.perp()
+                };
+                let knockback = away * consts::ENEMY_ROOK_TRAMPLE_KNOCKBACK_SPEED;
+                other
+                    .status_effects
+                    .apply(status_effect::StatusEffect::Knockback(knockback));
+
+                damage_numbers.push(damage_number::DamageNumber::new(
+                    format!("{}", (damage * 10.0).round() / 10.0),
+                    consts::DAMAGE_NUMBER_TIME,
+                    other.pos,
+                    damage_number::DamageNumberColor::EnemyDamage,
+                ));
             }
+        }
+        damage_numbers
+    }
+
+    // enemies left far behind are respawned ahead of where the player is going
+    fn recycle_stragglers(&mut self, player: &player::Player, max_dist: f32) {
+        let max_range = consts::ENEMY_RECYCLE_RANGE_MULT * max_dist;
+        let (stragglers, enemies) = std::mem::take(&mut self.enemies)
+            .into_iter()
+            .partition::<Vec<_>, _>(|enemy| enemy.pos.distance(player.pos) > max_range);
+        self.enemies = enemies;
 
-            self.spawn_enemy(player);
+        let ahead = if player.movement == mq::Vec2::ZERO {
+            None
+        } else {
+            Some(player.movement.y.atan2(player.movement.x))
+        };
+        let stragglers = stragglers
+            .into_iter()
+            .map(|mut enemy| {
+                let angle = match ahead {
+                    Some(angle) => {
+                        angle
+                            + mq::rand::gen_range(
+                                -consts::ENEMY_RECYCLE_SPREAD,
+                                consts::ENEMY_RECYCLE_SPREAD,
+                            )
+                    }
+                    None => mq::rand::gen_range(0.0, 2.0 * std::f32::consts::PI),
+                };
+                enemy.recycle(self.off_screen_pos(angle));
+                enemy
+            })
+            .collect();
+        self.telegraph_spawns(stragglers);
+    }
+
+    fn steer_around_neighbors(&mut self, delta: f32) {
+        let mut spatial_hash = spatial_hash::SpatialHash::new(consts::ENEMY_NEIGHBOR_CELL_SIZE);
+        for (i, enemy) in self.enemies.iter().enumerate() {
+            spatial_hash.insert(i, enemy.pos);
         }
 
-        (EnemiesKilled {
-            count,
-            super_killed,
-        },
-        damage_numbers)
+        let steering = self
+            .enemies
+            .iter()
+            .enumerate()
+            .map(|(i, enemy)| {
+                let mut separation = mq::Vec2::ZERO;
+                let mut flock_heading = mq::Vec2::ZERO;
+                let mut flock_center = mq::Vec2::ZERO;
+                let mut flock_count = 0;
+
+                for j in spatial_hash.nearby(enemy.pos) {
+                    if i == j {
+                        continue;
+                    }
+                    let other = &self.enemies[j];
+                    let offset = enemy.pos - other.pos;
+                    let distance = offset.length();
+
+                    // bigger enemies get pushed around less
+                    let combined_radius = enemy.radius() + other.radius();
+                    let min_distance = combined_radius * consts::ENEMY_SEPARATION_SPACING;
+                    if distance < min_distance {
+                        let overlap = 1.0 - distance / min_distance;
+                        let away = if distance > 0.0 {
+                            offset / distance
+                        } else {
+                            // exactly stacked, split them up deterministically
+                            mq::Vec2::from_angle(i as f32)
+                        };
+                        separation += away * overlap * other.radius() / combined_radius;
+                    }
+
+                    if enemy.flocking && other.flocking && distance < consts::ENEMY_FLOCK_RADIUS {
+                        flock_heading += mq::Vec2::from_angle(other.direction);
+                        flock_center += other.pos;
+                        flock_count += 1;
+                    }
+                }
+
+                let mut steering = separation * consts::ENEMY_SEPARATION_STRENGTH;
+                if flock_count > 0 {
+                    let alignment = flock_heading.normalize_or_zero();
+                    let cohesion =
+                        (flock_center / flock_count as f32 - enemy.pos).normalize_or_zero();
+                    steering += (alignment * consts::ENEMY_FLOCK_ALIGNMENT
+                        + cohesion * consts::ENEMY_FLOCK_COHESION)
+                        * enemy.speed;
+                }
+                steering
+            })
+            .collect::<Vec<_>>();
+
+        for (enemy, steering) in self.enemies.iter_mut().zip(steering) {
+            enemy.pos += steering * delta;
+        }
     }
 
-    fn spawn_enemy(&mut self, player: &player::Player) {
+    pub fn add_enemy_bullets(&mut self, bullets: Vec<bullet::Bullet>) {
+        self.enemy_bullets.extend(bullets);
+    }
+
+    // how many more enemies fit under the live cap
+    fn room(&self) -> usize {
+        self.wave_config
+            .max_enemies()
+            .saturating_sub(self.enemies.len() + self.spawn_telegraphs.len())
+    }
+
+    fn spawn_splits(&mut self, pos: mq::Vec2) {
+        for i in 0..consts::ENEMY_SPLITTING_COUNT.min(self.room()) {
+            let angle =
+                2.0 * std::f32::consts::PI * i as f32 / consts::ENEMY_SPLITTING_COUNT as f32;
+            let offset = mq::Vec2::new(angle.cos(), angle.sin()) * consts::ENEMY_SPLITTING_OFFSET;
+
+            let mut enemy = self.new_wave_enemy(pos + offset, EnemyType::Melee);
+            enemy.health *= consts::ENEMY_SPLITTING_HP_MOD;
+            enemy.max_health = enemy.health;
+            self.enemies.push(enemy);
+        }
+    }
+
+    fn roll_affixes(&self, enemy: Enemy) -> Enemy {
+        if self.wave < consts::ENEMY_ELITE_WAVE_START {
+            return enemy;
+        }
+        let affixes = affix::Affix::roll(self.wave, enemy.enemy_type.can_be_vampiric());
+        enemy.with_affixes(affixes)
+    }
+
+    fn spawn_boss(&mut self, player: &player::Player) {
+        // the arena is centered on the player so the fight starts right away
         let random_angle = mq::rand::gen_range(0.0, 2.0 * std::f32::consts::PI);
         let spawn_pos = mq::Vec2::new(random_angle.cos(), random_angle.sin())
-            * consts::ENEMY_SPAWN_RADIUS
+            * consts::ENEMY_BOSS_ARENA_RADIUS
             + player.pos;
 
-        let enemy_type = if self.should_spawn_super {
-            self.should_spawn_super = false;
-            EnemyType::Super
-        } else if mq::rand::gen_range(0.0, 1.0) < consts::ENEMY_RANGED_CHANCE {
-            EnemyType::Ranged
-        } else {
-            EnemyType::Melee
+        let enemy = Enemy::new(
+            spawn_pos,
+            self.wave_config.hp(self.wave) * consts::ENEMY_BOSS_HP_MOD(self.wave),
+            consts::ENEMY_DAMAGE,
+            self.wave_config.speed(self.wave) * consts::ENEMY_BOSS_SPEED_MOD,
+            EnemyType::King,
+            self.next_enemy_id,
+        );
+        self.next_enemy_id += 1;
+
+        self.boss_arena = Some(player.pos);
+        self.enemies.push(enemy);
+    }
+
+    // just outside the visible area, in the direction of the angle from its center
+    fn off_screen_pos(&self, angle: f32) -> mq::Vec2 {
+        let direction = mq::Vec2::new(angle.cos(), angle.sin());
+        let half_size = self.view.size() / 2.0 + mq::Vec2::splat(consts::ENEMY_SPAWN_MARGIN);
+        let distance_to_edge = (half_size / direction.abs()).min_element();
+        self.view.center() + direction * distance_to_edge
+    }
+
+    fn spawn_wave_event(&mut self, event: wave_config::WaveEvent) {
+        match event.spawn {
+            wave_config::WaveSpawn::Enemy(enemy_type) => self.spawn_enemy_type(enemy_type),
+            wave_config::WaveSpawn::Formation(formation) => self.spawn_formation(formation),
+        }
+    }
+
+    fn telegraph_spawns(&mut self, enemies: Vec<Enemy>) {
+        self.spawn_telegraphs.extend(
+            enemies
+                .into_iter()
+                .map(|enemy| (enemy, consts::ENEMY_SPAWN_TELEGRAPH_TIME)),
+        );
+    }
+
+    fn spawn_formation(&mut self, formation: Formation) {
+        let random_angle = mq::rand::gen_range(0.0, 2.0 * std::f32::consts::PI);
+        let outward = mq::Vec2::new(random_angle.cos(), random_angle.sin());
+        let center = self.off_screen_pos(random_angle);
+        let sideways = outward.perp();
+
+        // offsets along a line, centered on the spawn point
+        let line = |count: usize, spacing: f32| {
+            (0..count)
+                .map(move |i| center + sideways * spacing * (i as f32 - (count - 1) as f32 / 2.0))
+        };
+
+        let enemies = match formation {
+            Formation::PawnWall => {
+                let direction = random_angle + std::f32::consts::PI;
+                line(consts::ENEMY_WALL_COUNT, consts::ENEMY_WALL_SPACING)
+                    .map(|pos| {
+                        self.new_wave_enemy(pos, EnemyType::Melee)
+                            .with_movement(EnemyMovementType::March(direction))
+                    })
+                    .collect::<Vec<_>>()
+            }
+            Formation::Ring => (0..consts::ENEMY_RING_COUNT)
+                .map(|i| {
+                    let angle =
+                        2.0 * std::f32::consts::PI * i as f32 / consts::ENEMY_RING_COUNT as f32;
+                    let pos = self.off_screen_pos(angle);
+                    self.new_wave_enemy(pos, EnemyType::Melee)
+                        .with_movement(EnemyMovementType::Chase)
+                })
+                .collect(),
+            Formation::BishopSquad => line(consts::ENEMY_SQUAD_COUNT, consts::ENEMY_SQUAD_SPACING)
+                .map(|pos| self.new_wave_enemy(pos, EnemyType::Ranged))
+                .collect(),
+            Formation::Swarm => (0..consts::ENEMY_SWARM_COUNT)
+                .map(|_| {
+                    let angle = mq::rand::gen_range(0.0, 2.0 * std::f32::consts::PI);
+                    let dist = mq::rand::gen_range(0.0, consts::ENEMY_SWARM_RADIUS);
+                    let pos = center + mq::Vec2::new(angle.cos(), angle.sin()) * dist;
+
+                    let mut enemy = self.new_wave_enemy(pos, EnemyType::Melee);
+                    enemy.health *= consts::ENEMY_SWARM_HP_MOD;
+                    enemy.max_health = enemy.health;
+                    enemy.speed *= consts::ENEMY_SWARM_SPEED_MOD;
+                    enemy.flocking = true;
+                    enemy
+                })
+                .collect(),
         };
 
-        let mut hp = consts::ENEMY_WAVE_HP(self.wave);
-        if enemy_type == EnemyType::Super {
-            hp *= consts::ENEMY_SUPER_HP_MOD(self.wave);
+        // anything over the cap is left for later spawns in the wave
+        let enemies = enemies
+            .into_iter()
+            .take(self.room())
+            .map(|enemy| self.roll_affixes(enemy))
+            .collect::<Vec<_>>();
+
+        self.enemies_until_next_wave -= enemies.len() as i32;
+        self.telegraph_spawns(enemies);
+    }
+
+    fn new_wave_enemy(&mut self, pos: mq::Vec2, enemy_type: EnemyType) -> Enemy {
+        let mut hp = self.wave_config.hp(self.wave);
+        match enemy_type {
+            EnemyType::Super => hp *= consts::ENEMY_SUPER_HP_MOD(self.wave),
+            EnemyType::Rook => hp *= consts::ENEMY_ROOK_HP_MOD,
+            _ => {}
         }
 
         let enemy = Enemy::new(
-            spawn_pos,
+            pos,
             hp,
             consts::ENEMY_DAMAGE,
-            consts::ENEMY_WAVE_SPEED(self.wave),
+            self.wave_config.speed(self.wave),
             enemy_type,
             self.next_enemy_id,
         );
         self.next_enemy_id += 1;
+        enemy
+    }
+
+    fn spawn_enemy(&mut self) {
+        let enemy_type = if self.should_spawn_super {
+            self.should_spawn_super = false;
+            EnemyType::Super
+        } else if let Some(mix) = self.wave_config.mix(self.wave) {
+            // weighted pick, falls back to a pawn if every weight is 0
+            let total_weight = mix.iter().map(|(_, weight)| weight).sum::<f32>();
+            let mut roll = mq::rand::gen_range(0.0, total_weight);
+            mix.iter()
+                .find(|(_, weight)| {
+                    roll -= weight;
+                    roll < 0.0
+                })
+                .map_or(EnemyType::Melee, |(enemy_type, _)| *enemy_type)
+        } else if self.wave >= consts::ENEMY_ROOK_WAVE_START
+            && mq::rand::gen_range(0.0, 1.0) < consts::ENEMY_ROOK_CHANCE
+        {
+            EnemyType::Rook
+        } else if self.wave >= consts::ENEMY_KNIGHT_WAVE_START
+            && mq::rand::gen_range(0.0, 1.0) < consts::ENEMY_KNIGHT_CHANCE
+        {
+            EnemyType::Knight
+        } else if mq::rand::gen_range(0.0, 1.0) < consts::ENEMY_RANGED_CHANCE {
+            EnemyType::Ranged
+        } else {
+            EnemyType::Melee
+        };
+
+        self.spawn_enemy_type(enemy_type);
+    }
+
+    fn spawn_enemy_type(&mut self, enemy_type: EnemyType) {
+        if self.room() == 0 {
+            return;
+        }
+        let random_angle = mq::rand::gen_range(0.0, 2.0 * std::f32::consts::PI);
+        let spawn_pos = self.off_screen_pos(random_angle);
+
+        let enemy = self.new_wave_enemy(spawn_pos, enemy_type);
+        let enemy = self.roll_affixes(enemy);
 
         self.enemies_until_next_wave -= 1;
-        self.enemies.push(enemy);
+        self.telegraph_spawns(vec![enemy]);
     }
 
     pub fn draw(&self, camera: &camera::Camera, chess_texture: &mq::Texture2D, scale: f32) {
+        if let Some(center) = self.boss_arena {
+            let draw_pos = (center - camera.pos) * scale / consts::TILES_PER_SCALE as f32
+                + mq::Vec2::new(mq::screen_width() / 2.0, mq::screen_height() / 2.0);
+            mq::draw_circle_lines(
+                draw_pos.x,
+                draw_pos.y,
+                consts::ENEMY_BOSS_ARENA_RADIUS * scale / consts::TILES_PER_SCALE as f32,
+                consts::ENEMY_BOSS_ARENA_THICKNESS * scale,
+                colors::NORD11,
+            );
+        }
+
+        // spawn telegraphs fill up until the enemy appears
+        // enemies spawn off screen, so the telegraph is pulled in to the edge of the screen
+        for (enemy, time_left) in self.spawn_telegraphs.iter() {
+            let draw_radius = enemy.radius() * scale / consts::TILES_PER_SCALE as f32;
+            let inset = draw_radius + consts::ENEMY_SPAWN_TELEGRAPH_INSET * scale;
+            let draw_pos = ((enemy.pos - camera.pos) * scale / consts::TILES_PER_SCALE as f32
+                + mq::Vec2::new(mq::screen_width() / 2.0, mq::screen_height() / 2.0))
+            .clamp(
+                mq::Vec2::splat(inset),
+                mq::Vec2::new(mq::screen_width() - inset, mq::screen_height() - inset),
+            );
+            let spawn_ratio = 1.0 - time_left / consts::ENEMY_SPAWN_TELEGRAPH_TIME;
+
+            mq::draw_circle(
+                draw_pos.x,
+                draw_pos.y,
+                draw_radius * spawn_ratio.clamp(0.0, 1.0),
+                colors::NORD11_BIG_ALPHA,
+            );
+            mq::draw_circle_lines(
+                draw_pos.x,
+                draw_pos.y,
+                draw_radius,
+                consts::ENEMY_SPAWN_TELEGRAPH_THICKNESS * scale,
+                colors::NORD11_ALPHA,
+            );
+        }
+
         for enemy in self.enemies.iter() {
             enemy.draw(camera, chess_texture, scale);
         }
@@ -554,9 +1606,50 @@ impl EnemyManager {
         }
     }
 
-    pub fn draw_hp_bars(&self, camera: &camera::Camera, scale: f32) {
+    pub fn draw_hp_bars(&self, camera: &camera::Camera, font: &mq::Font, scale: f32) {
         for enemy in self.enemies.iter() {
-            enemy.draw_hp_bar(camera, scale);
+            if enemy.enemy_type != EnemyType::King {
+                enemy.draw_hp_bar(camera, font, scale);
+            }
         }
     }
+
+    pub fn draw_boss_bar(&self, font: &mq::Font, top_y: f32, scale: f32) {
+        let boss = match self
+            .enemies
+            .iter()
+            .find(|enemy| enemy.enemy_type == EnemyType::King)
+        {
+            Some(boss) => boss,
+            None => return,
+        };
+
+        let bar_width = mq::screen_width() * consts::ENEMY_BOSS_BAR_WIDTH;
+        let bar_height = scale * consts::ENEMY_BOSS_BAR_HEIGHT;
+        let bar_thickness = scale * consts::ENEMY_BOSS_BAR_THICKNESS;
+
+        let x = mq::screen_width() / 2.0 - bar_width / 2.0;
+        let y = top_y + scale * consts::ENEMY_BOSS_BAR_TOP_OFFSET;
+
+        let hp_ratio = (boss.health / boss.max_health).max(0.0);
+        mq::draw_rectangle(x, y, bar_width, bar_height, colors::NORD6_ALPHA);
+        mq::draw_rectangle(x, y, bar_width * hp_ratio, bar_height, colors::NORD11);
+        mq::draw_rectangle_lines(x, y, bar_width, bar_height, bar_thickness, colors::NORD6);
+
+        let text = format!("King - Phase {}", boss.boss_phase().number());
+        let font_size = (bar_height * consts::ENEMY_BOSS_BAR_FONT_RATIO).round() as u16;
+        let text_dims = mq::measure_text(&text, Some(font), font_size, 1.0);
+        mq::draw_text_ex(
+            &text,
+            mq::screen_width() / 2.0 - text_dims.width / 2.0,
+            y + bar_height / 2.0 + text_dims.offset_y / 2.25,
+            mq::TextParams {
+                font: Some(font),
+                font_size,
+                font_scale: 1.0,
+                color: colors::NORD0,
+                ..mq::TextParams::default()
+            },
+        );
+    }
 }
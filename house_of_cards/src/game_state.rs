@@ -5,6 +5,8 @@ pub enum GameState {
     Paused,
     ChooseCard,
     PowerupCard,
+    DeckEdit,
+    DeckViewer,
 }
 
 impl GameState {
@@ -21,6 +23,28 @@ impl GameState {
     }
 }
 
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum GameMode {
+    Classic,    // cards can be shot forever
+    DeckAsAmmo, // cards are discarded once their shots run out
+}
+
+impl GameMode {
+    pub fn name(&self) -> &str {
+        match self {
+            GameMode::Classic => "Classic",
+            GameMode::DeckAsAmmo => "Deck as ammo",
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        *self = match self {
+            GameMode::Classic => GameMode::DeckAsAmmo,
+            GameMode::DeckAsAmmo => GameMode::Classic,
+        }
+    }
+}
+
 pub struct GameStateManager {
     pub current_state: GameState,
     last_states: Vec<GameState>,
@@ -37,7 +61,11 @@ impl GameStateManager {
     pub fn show_mouse(&self) -> bool {
         matches!(
             self.current_state,
-            GameState::Paused | GameState::ChooseCard | GameState::PowerupCard
+            GameState::Paused
+                | GameState::ChooseCard
+                | GameState::PowerupCard
+                | GameState::DeckEdit
+                | GameState::DeckViewer
         )
     }
 
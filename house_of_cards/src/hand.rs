@@ -1,9 +1,36 @@
-use crate::{colors, consts, deck, util, weapon};
+use crate::{bullet, colors, combo, consts, deck, game_state, stats, util, weapon};
 use macroquad::prelude as mq;
 
 struct Slot {
     card: deck::Card,
     weapon: weapon::Weapon,
+    shots_fired: u32,
+    mirror: Option<deck::Card>, // card copied by a joker
+}
+
+impl Slot {
+    fn new(card: deck::Card) -> Self {
+        Self {
+            card,
+            weapon: card.get_weapon(),
+            shots_fired: 0,
+            mirror: None,
+        }
+    }
+
+    fn effective_card(&self) -> deck::Card {
+        self.mirror.unwrap_or(self.card)
+    }
+
+    fn is_broken(&self) -> bool {
+        self.card
+            .max_shots()
+            .is_some_and(|max_shots| self.shots_fired >= max_shots)
+    }
+
+    fn ammo_left(&self) -> u32 {
+        self.card.ammo().saturating_sub(self.shots_fired)
+    }
 }
 
 pub struct HandDrawDimensions {
@@ -15,15 +42,18 @@ pub struct HandDrawDimensions {
 }
 
 pub struct Hand {
-    slots: Vec<Slot>,  // len = consts::HAND_CARD_COUNT
-    pub active: usize, // Index of active card (0-consts::HAND_CARD_COUNT-1)
+    slots: Vec<Slot>,  // len = consts::HAND_MIN_SIZE to consts::HAND_MAX_SIZE
+    pub active: usize, // Index of active card (0-slots.len()-1)
+    combo: combo::Combo,
+    pub auto_cycle: bool,
+    pub game_mode: game_state::GameMode,
 }
 impl Hand {
     pub fn new(deck: &mut deck::Deck) -> Self {
-        let mut slots = Vec::with_capacity(consts::HAND_CARD_COUNT);
+        let mut slots = Vec::with_capacity(consts::HAND_START_SIZE);
         let mut discarded_cards = Vec::new();
 
-        while slots.len() < consts::HAND_CARD_COUNT {
+        while slots.len() < consts::HAND_START_SIZE {
             let card = deck.draw_card();
             if card.is_ace()
                 || card.is_face()
@@ -34,16 +64,23 @@ impl Hand {
                 continue;
             }
 
-            let weapon = card.get_weapon();
-            slots.push(Slot { card, weapon });
+            slots.push(Slot::new(card));
         }
 
         for card in discarded_cards {
-            deck.add_card(card);
+            deck.return_card(card);
         }
         deck.shuffle();
 
-        Self { slots, active: 0 }
+        let mut hand = Self {
+            slots,
+            active: 0,
+            combo: combo::Combo::None,
+            auto_cycle: false,
+            game_mode: game_state::GameMode::Classic,
+        };
+        hand.on_hand_changed();
+        hand
     }
     pub fn active_weapon(&self) -> &weapon::Weapon {
         &self.slots[self.active].weapon
@@ -53,30 +90,219 @@ impl Hand {
         self.slots[self.active].card
     }
 
-    pub fn get_ms_penalty(&self) -> f32 {
-        self.active_weapon().get_ms_penalty()
+    // the card that is actually shot (the copied card for jokers)
+    pub fn active_effective_card(&self) -> deck::Card {
+        self.slots[self.active].effective_card()
     }
 
-    pub fn update(&mut self, delta: f32) {
+    pub fn active_bullet_damage(&self) -> bullet::BulletDamage {
+        let slot = &self.slots[self.active];
+        match slot.mirror {
+            Some(mirror) => bullet::BulletDamage::Wild(mirror, slot.card.joker_damage_mod()),
+            None => bullet::BulletDamage::Card(slot.card),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn can_grow(&self) -> bool {
+        self.slots.len() < consts::HAND_MAX_SIZE
+    }
+
+    pub fn can_shrink(&self) -> bool {
+        self.slots.len() > consts::HAND_MIN_SIZE
+    }
+
+    pub fn grow(&mut self, deck: &mut deck::Deck) {
+        if self.can_grow() {
+            self.slots.push(Slot::new(deck.draw_card()));
+            self.on_hand_changed();
+        }
+    }
+
+    pub fn shrink(&mut self, deck: &mut deck::Deck) {
+        if self.can_shrink() {
+            let slot = self.slots.pop().unwrap();
+            deck.discard(slot.card);
+
+            self.active = self.active.min(self.slots.len() - 1);
+            self.on_hand_changed();
+        }
+    }
+
+    fn on_hand_changed(&mut self) {
+        let cards = self.slots.iter().map(|slot| slot.card).collect::<Vec<_>>();
+        self.combo = combo::Combo::evaluate(&cards);
+
+        // jokers copy the strongest other card in the hand
+        for i in 0..self.slots.len() {
+            if self.slots[i].card.suit != deck::Suit::Joker {
+                continue;
+            }
+
+            let mirror = cards
+                .iter()
+                .enumerate()
+                .filter(|(j, card)| *j != i && card.suit != deck::Suit::Joker)
+                .max_by_key(|(_, card)| card.strength())
+                .map(|(_, card)| *card);
+
+            let slot = &mut self.slots[i];
+            if slot.mirror == mirror {
+                continue;
+            }
+            slot.mirror = mirror;
+
+            let mut weapon = match mirror {
+                Some(mirror) => {
+                    let mut weapon = mirror.get_weapon();
+                    weapon.fire_rate *= slot.card.joker_fire_rate_mod();
+                    weapon
+                }
+                None => slot.card.get_weapon(),
+            };
+            // don't reset the cooldown
+            weapon.time_until_next_shot =
+                slot.weapon.time_until_next_shot.min(1.0 / weapon.fire_rate);
+            slot.weapon = weapon;
+        }
+    }
+
+    // bonuses from the cards being held
+    pub fn stat_modifiers(&self) -> Vec<stats::Modifier> {
+        vec![
+            stats::Modifier::Mul(
+                stats::Stat::MoveSpeed,
+                self.active_weapon().get_ms_penalty(),
+            ),
+            stats::Modifier::Mul(stats::Stat::FireRate, self.combo.fire_rate_mod()),
+            stats::Modifier::Mul(stats::Stat::Damage, self.combo.damage_mod()),
+            stats::Modifier::Add(stats::Stat::Pierce, self.combo.extra_pierce() as f32),
+        ]
+    }
+
+    pub fn update(&mut self, delta: f32, fire_rate_mod: f32) {
         for slot in self.slots.iter_mut() {
-            slot.weapon.update(delta);
+            slot.weapon.update(delta * fire_rate_mod);
+        }
+
+        if self.auto_cycle {
+            self.cycle();
         }
     }
 
-    pub fn set_card(&mut self, card: deck::Card) {
-        let weapon = card.get_weapon();
-        self.slots[self.active] = Slot { card, weapon };
+    fn upcoming_order(&self) -> Vec<usize> {
+        // active slot first, then the others by when they are ready to shoot
+        // (ties are broken by going around the hand from the active slot)
+        let len = self.slots.len();
+        let mut order = (1..len)
+            .map(|i| (self.active + i) % len)
+            .collect::<Vec<_>>();
+        order.sort_by(|a, b| {
+            let time_a = self.slots[*a].weapon.time_until_next_shot;
+            let time_b = self.slots[*b].weapon.time_until_next_shot;
+            time_a.partial_cmp(&time_b).unwrap()
+        });
+        order.insert(0, self.active);
+        order
+    }
+
+    fn cycle(&mut self) {
+        if let Some(next) = self.upcoming_order().get(1).copied() {
+            let active_time = self.slots[self.active].weapon.time_until_next_shot;
+            let next_time = self.slots[next].weapon.time_until_next_shot;
+            if next_time < active_time {
+                self.active = next;
+            }
+        }
+    }
+
+    pub fn set_card(&mut self, card: deck::Card, deck: &mut deck::Deck) {
+        deck.discard(self.active_card());
+
+        self.slots[self.active] = Slot::new(card);
+        self.on_hand_changed();
+    }
+
+    fn fuse_partner(&self) -> Option<usize> {
+        let active_card = self.active_card();
+        if !active_card.can_fuse() {
+            return None;
+        }
+
+        // fuse with the highest other card of the same suit
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(|(i, slot)| {
+                *i != self.active && slot.card.suit == active_card.suit && slot.card.can_fuse()
+            })
+            .max_by_key(|(_, slot)| slot.card.value)
+            .map(|(i, _)| i)
+    }
+
+    pub fn can_fuse(&self) -> bool {
+        self.fuse_partner().is_some()
+    }
+
+    pub fn fuse(&mut self, deck: &mut deck::Deck) {
+        if let Some(partner) = self.fuse_partner() {
+            let fused_card = self.active_card().fuse(&self.slots[partner].card);
+            deck.discard(self.active_card());
+            deck.discard(self.slots[partner].card);
+
+            self.slots[self.active] = Slot::new(fused_card);
+            self.slots[partner] = Slot::new(deck.draw_card());
+
+            self.on_hand_changed();
+        }
     }
 
     pub fn try_shoot(&mut self) -> util::Shot {
-        self.slots[self.active].weapon.try_shoot()
+        let slot = &mut self.slots[self.active];
+        let shot = slot.weapon.try_shoot();
+        if shot.0 {
+            slot.shots_fired += 1;
+        }
+        shot
+    }
+
+    pub fn active_card_broken(&self) -> bool {
+        self.slots[self.active].is_broken()
     }
 
-    pub fn hand_draw_dimensions(scale: f32) -> HandDrawDimensions {
+    pub fn break_active_card(&mut self, deck: &mut deck::Deck) {
+        // broken cards are gone for the rest of the run
+        let card = self.active_card();
+        deck.remove_card(card);
+        deck.discard(card);
+
+        self.slots[self.active] = Slot::new(deck.draw_card());
+        self.on_hand_changed();
+    }
+
+    fn uses_ammo(&self) -> bool {
+        self.game_mode == game_state::GameMode::DeckAsAmmo
+    }
+
+    pub fn active_card_out_of_ammo(&self) -> bool {
+        self.uses_ammo() && self.slots[self.active].ammo_left() == 0
+    }
+
+    pub fn refill_active_card(&mut self, deck: &mut deck::Deck) {
+        deck.discard(self.active_card());
+
+        self.slots[self.active] = Slot::new(deck.draw_card());
+        self.on_hand_changed();
+    }
+
+    pub fn hand_draw_dimensions(hand_size: usize, scale: f32) -> HandDrawDimensions {
         let max_width = consts::HAND_TOTAL_MAX_WIDTH * scale;
         let max_height = consts::HAND_TOTAL_MAX_HEIGHT * scale;
 
-        let card_count = consts::HAND_CARD_COUNT as f32;
+        let card_count = hand_size as f32;
         let spacing_count = card_count - 1.0;
         let total_width = consts::CARD_PX_WIDTH * card_count
             + consts::HAND_SPACING * consts::CARD_PX_WIDTH * spacing_count;
@@ -104,13 +330,15 @@ impl Hand {
             card_width,
             card_height,
             spacing,
-        } = Self::hand_draw_dimensions(scale);
+        } = Self::hand_draw_dimensions(self.slots.len(), scale);
 
         let outline_width = card_width * (1.0 + consts::HAND_SPACING);
         let outline_y = y - consts::HAND_SPACING * card_width / 2.0;
         let outline_height = card_height + card_width * consts::HAND_SPACING;
         let outline_thickness = consts::HAND_OUTLINE_THICKNESS * scale;
 
+        let upcoming_order = self.upcoming_order();
+
         for (i, slot) in self.slots.iter().enumerate() {
             let card = &slot.card;
             let weapon = &slot.weapon;
@@ -145,29 +373,33 @@ impl Hand {
                     colors::NORD5,
                 );
             } else {
+                let next_up = self.auto_cycle && upcoming_order.get(1) == Some(&i);
                 mq::draw_rectangle(
                     outline_x,
                     inner_y,
                     outline_width,
                     inner_height,
-                    colors::NORD11,
+                    if next_up {
+                        colors::NORD13
+                    } else {
+                        colors::NORD11
+                    },
                 );
             }
 
-            let texture_source = card.get_texture_source();
-            mq::draw_texture_ex(
+            card.draw(
                 cards_texture,
-                x,
-                y,
-                mq::WHITE,
-                mq::DrawTextureParams {
-                    dest_size: Some(mq::Vec2::new(card_width, card_height)),
-                    source: Some(texture_source),
-                    ..Default::default()
-                },
+                mq::Rect::new(x, y, card_width, card_height),
+                scale,
             );
 
-            let text = format!("{}", i + 1);
+            // show the upcoming order instead of the slot keys when auto cycling
+            let (text, text_color) = if self.auto_cycle {
+                let order = upcoming_order.iter().position(|j| *j == i).unwrap();
+                (format!("{}", order + 1), colors::NORD13)
+            } else {
+                (format!("{}", i + 1), colors::NORD5)
+            };
             let font_size = (consts::HAND_NUMBER_ICON_FONT_SIZE * scale).round() as u16;
             let text_dims = mq::measure_text(&text, Some(font), font_size, 1.0);
             mq::draw_text_ex(
@@ -178,14 +410,65 @@ impl Hand {
                     font: Some(font),
                     font_size,
                     font_scale: 1.0,
-                    color: colors::NORD5,
+                    color: text_color,
                     ..mq::TextParams::default()
                 },
             );
 
+            if self.uses_ammo() {
+                let text = format!("{}/{}", slot.ammo_left(), card.ammo());
+                let font_size = (consts::HAND_AMMO_FONT_SIZE * scale).round() as u16;
+                let text_dims = mq::measure_text(&text, Some(font), font_size, 1.0);
+                mq::draw_text_ex(
+                    &text,
+                    x + (card_width - text_dims.width) / 2.0,
+                    outline_y + outline_height + text_dims.offset_y,
+                    mq::TextParams {
+                        font: Some(font),
+                        font_size,
+                        font_scale: 1.0,
+                        color: colors::NORD4,
+                        ..mq::TextParams::default()
+                    },
+                );
+            }
+
             x += card_width + spacing;
         }
 
+        if self.combo != combo::Combo::None {
+            let font_size = (consts::HAND_COMBO_FONT_SIZE * scale).round() as u16;
+            let name_dims = mq::measure_text(self.combo.name(), Some(font), font_size, 1.0);
+            let text_x = x - spacing / 2.0 + outline_thickness * 2.0;
+            let text_y = y + card_height / 2.0;
+
+            mq::draw_text_ex(
+                self.combo.name(),
+                text_x,
+                text_y,
+                mq::TextParams {
+                    font: Some(font),
+                    font_size,
+                    font_scale: 1.0,
+                    color: colors::NORD13,
+                    ..mq::TextParams::default()
+                },
+            );
+            mq::draw_text_ex(
+                self.combo.bonus_text(),
+                text_x,
+                text_y + name_dims.height * 1.25,
+                mq::TextParams {
+                    font: Some(font),
+                    font_size: (font_size as f32 * consts::HAND_COMBO_SUB_FONT_RATIO).round()
+                        as u16,
+                    font_scale: 1.0,
+                    color: colors::NORD4,
+                    ..mq::TextParams::default()
+                },
+            );
+        }
+
         y
     }
 }
@@ -193,14 +476,17 @@ impl Hand {
 pub struct CardChoicesButtonRects {
     pub cards: Vec<mq::Rect>,
     pub swap_button: mq::Rect,
+    pub fuse_button: mq::Rect,
     pub discard_button: mq::Rect,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn draw_card_choices(
     card_choices: &[deck::Card],
     cards_texture: &mq::Texture2D,
     font: &mq::Font,
     selected: usize,
+    can_fuse: bool,
     score_text_bottom_y: f32,
     hand_top_y: f32,
     scale: f32,
@@ -248,76 +534,123 @@ pub fn draw_card_choices(
             );
         }
 
-        let texture_source = card.get_texture_source();
-        mq::draw_texture_ex(
+        card.draw(
             cards_texture,
-            x,
-            y,
-            mq::WHITE,
-            mq::DrawTextureParams {
-                dest_size: Some(mq::Vec2::new(card_width, total_height)),
-                source: Some(texture_source),
-                ..Default::default()
-            },
+            mq::Rect::new(x, y, card_width, total_height),
+            scale,
         );
 
+        if let Some(enchantment) = card.enchantment {
+            let font_size = (consts::CARD_CHOICE_ENCHANTMENT_FONT_SIZE * scale).round() as u16;
+            let text = format!("{}: {}", enchantment.name(), enchantment.description());
+            let text_dims = mq::measure_text(&text, Some(font), font_size, 1.0);
+            mq::draw_text_ex(
+                &text,
+                x + card_width / 2.0 - text_dims.width / 2.0,
+                outline_y + outline_height + text_dims.offset_y * 1.25,
+                mq::TextParams {
+                    font: Some(font),
+                    font_size,
+                    font_scale: 1.0,
+                    color: enchantment.color(),
+                    ..mq::TextParams::default()
+                },
+            );
+        }
+
         cards_button_rects.push(mq::Rect::new(x, y, card_width, total_height));
 
         x += card_width + consts::CARD_CHOICE_SPACING * card_width;
     }
 
-    let button_height = (total_height - consts::CARD_CHOICE_SPACING * card_width) / 2.0;
-    let y2 = y + total_height - button_height;
-    mq::draw_rectangle(x, y, card_width, button_height, colors::NORD14_BIG_ALPHA);
-    mq::draw_rectangle_lines(
-        x,
-        y,
-        card_width,
-        button_height,
-        outline_thickness * 2.0,
-        colors::NORD14,
-    );
+    let button_count = 3.0;
+    let button_spacing = consts::CARD_CHOICE_SPACING * card_width;
+    let button_height = (total_height - button_spacing * (button_count - 1.0)) / button_count;
 
     let swap_button_rect = mq::Rect::new(x, y, card_width, button_height);
-
-    let text = "Swap";
-    let font_size = (consts::CARD_CHOICE_FONT_SIZE * scale).round() as u16;
-    let text_dims = mq::measure_text(text, Some(font), font_size, 1.0);
-    let text_pos = mq::Vec2::new(
-        x + card_width / 2.0 - text_dims.width / 2.0,
-        y + button_height / 2.0 + text_dims.offset_y / 2.25,
+    draw_choice_button(
+        swap_button_rect,
+        "Swap",
+        colors::NORD14,
+        colors::NORD14_BIG_ALPHA,
+        font,
+        scale,
     );
-    mq::draw_text_ex(
-        text,
-        text_pos.x,
-        text_pos.y,
-        mq::TextParams {
-            font: Some(font),
-            font_size,
-            font_scale: 1.0,
-            color: colors::NORD14,
-            ..mq::TextParams::default()
-        },
+
+    let fuse_button_rect = mq::Rect::new(
+        x,
+        y + button_height + button_spacing,
+        card_width,
+        button_height,
     );
+    if can_fuse {
+        draw_choice_button(
+            fuse_button_rect,
+            "Fuse",
+            colors::NORD15,
+            colors::NORD15_BIG_ALPHA,
+            font,
+            scale,
+        );
+    } else {
+        draw_choice_button(
+            fuse_button_rect,
+            "Fuse",
+            colors::NORD3,
+            colors::NORD0_BIG_ALPHA,
+            font,
+            scale,
+        );
+    }
 
-    mq::draw_rectangle(x, y2, card_width, button_height, colors::NORD11_BIG_ALPHA);
-    mq::draw_rectangle_lines(
+    let discard_button_rect = mq::Rect::new(
         x,
-        y2,
+        y + total_height - button_height,
         card_width,
         button_height,
-        outline_thickness * 2.0,
+    );
+    draw_choice_button(
+        discard_button_rect,
+        "Discard All",
         colors::NORD11,
+        colors::NORD11_BIG_ALPHA,
+        font,
+        scale,
     );
 
-    let discard_button_rect = mq::Rect::new(x, y2, card_width, button_height);
+    CardChoicesButtonRects {
+        cards: cards_button_rects,
+        swap_button: swap_button_rect,
+        fuse_button: fuse_button_rect,
+        discard_button: discard_button_rect,
+    }
+}
+
+fn draw_choice_button(
+    rect: mq::Rect,
+    text: &str,
+    color: mq::Color,
+    fill_color: mq::Color,
+    font: &mq::Font,
+    scale: f32,
+) {
+    let outline_thickness = consts::CARD_CHOICE_OUTLINE_THICKNESS * scale;
+
+    mq::draw_rectangle(rect.x, rect.y, rect.w, rect.h, fill_color);
+    mq::draw_rectangle_lines(
+        rect.x,
+        rect.y,
+        rect.w,
+        rect.h,
+        outline_thickness * 2.0,
+        color,
+    );
 
-    let text = "Discard All";
     let font_size = (consts::CARD_CHOICE_FONT_SIZE * scale).round() as u16;
     let text_dims = mq::measure_text(text, Some(font), font_size, 1.0);
     let text_pos = mq::Vec2::new(
-        x + card_width / 2.0 - text_dims.width / 2.0,
-        y2 + button_height / 2.0 + text_dims.offset_y / 2.25,
+        rect.x + rect.w / 2.0 - text_dims.width / 2.0,
+        rect.y + rect.h / 2.0 + text_dims.offset_y / 2.25,
     );
     mq::draw_text_ex(
         text,
@@ -327,14 +660,8 @@ pub fn draw_card_choices(
             font: Some(font),
             font_size,
             font_scale: 1.0,
-            color: colors::NORD11,
+            color,
             ..mq::TextParams::default()
         },
     );
-
-    CardChoicesButtonRects {
-        cards: cards_button_rects,
-        swap_button: swap_button_rect,
-        discard_button: discard_button_rect,
-    }
 }
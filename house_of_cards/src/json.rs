@@ -0,0 +1,311 @@
+// just enough json to read the game's data files
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f32),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn parse(text: &str) -> Result<Json, String> {
+        let mut parser = Parser {
+            chars: text.chars().collect(),
+            index: 0,
+        };
+        let json = parser.value()?;
+        parser.skip_whitespace();
+        if parser.index < parser.chars.len() {
+            return Err(parser.error("trailing characters"));
+        }
+        Ok(json)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(entries) => entries
+                .iter()
+                .find(|(entry_key, _)| entry_key == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f32(&self) -> Option<f32> {
+        match self {
+            Json::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Json::Bool(boolean) => Some(*boolean),
+            _ => None,
+        }
+    }
+
+    pub fn as_i32(&self) -> Option<i32> {
+        self.as_f32().map(|number| number.round() as i32)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, Json)]> {
+        match self {
+            Json::Object(entries) => Some(entries),
+            _ => None,
+        }
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    index: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> String {
+        format!("{} at character {}", message, self.index)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.index += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.index += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", expected)))
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str, json: Json) -> Result<Json, String> {
+        for expected in keyword.chars() {
+            if self.peek() != Some(expected) {
+                return Err(self.error(&format!("expected '{}'", keyword)));
+            }
+            self.index += 1;
+        }
+        Ok(json)
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') => self.string().map(Json::String),
+            Some('t') => self.keyword("true", Json::Bool(true)),
+            Some('f') => self.keyword("false", Json::Bool(false)),
+            Some('n') => self.keyword("null", Json::Null),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, String> {
+        self.expect('{')?;
+        let mut entries = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.index += 1;
+            return Ok(Json::Object(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(':')?;
+            entries.push((key, self.value()?));
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.index += 1,
+                Some('}') => {
+                    self.index += 1;
+                    return Ok(Json::Object(entries));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, String> {
+        self.expect('[')?;
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.index += 1;
+            return Ok(Json::Array(values));
+        }
+        loop {
+            values.push(self.value()?);
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.index += 1,
+                Some(']') => {
+                    self.index += 1;
+                    return Ok(Json::Array(values));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if self.peek() != Some('"') {
+            return Err(self.error("expected string"));
+        }
+        self.index += 1;
+
+        let mut string = String::new();
+        loop {
+            match self.peek() {
+                Some('"') => {
+                    self.index += 1;
+                    return Ok(string);
+                }
+                Some('\\') => {
+                    self.index += 1;
+                    let escaped = match self.peek() {
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some(c @ ('"' | '\\' | '/')) => c,
+                        _ => return Err(self.error("unsupported escape")),
+                    };
+                    string.push(escaped);
+                    self.index += 1;
+                }
+                Some(c) => {
+                    string.push(c);
+                    self.index += 1;
+                }
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, String> {
+        let start = self.index;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            self.index += 1;
+        }
+        let text = self.chars[start..self.index].iter().collect::<String>();
+        text.parse::<f32>()
+            .map(Json::Number)
+            .map_err(|_| self.error("invalid number"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_documents() {
+        let json = Json::parse(
+            r#" { "name": "ring", "count": 12, "weights": [1, 2.5, -3e1], "nested": { "on": true, "off": false, "none": null } } "#,
+        )
+        .unwrap();
+
+        assert_eq!(json.get("name").and_then(Json::as_str), Some("ring"));
+        assert_eq!(json.get("count").and_then(Json::as_i32), Some(12));
+
+        let weights = json.get("weights").and_then(Json::as_array).unwrap();
+        let weights = weights.iter().filter_map(Json::as_f32).collect::<Vec<_>>();
+        assert_eq!(weights, vec![1.0, 2.5, -30.0]);
+
+        let nested = json.get("nested").unwrap();
+        assert_eq!(nested.get("on").and_then(Json::as_bool), Some(true));
+        assert_eq!(nested.get("off").and_then(Json::as_bool), Some(false));
+        assert!(matches!(nested.get("none"), Some(Json::Null)));
+        assert!(nested.get("missing").is_none());
+    }
+
+    #[test]
+    fn parses_empty_containers() {
+        let json = Json::parse(r#"{ "list": [], "object": {} }"#).unwrap();
+        assert_eq!(
+            json.get("list").and_then(Json::as_array).map(<[_]>::len),
+            Some(0)
+        );
+        assert_eq!(
+            json.get("object").and_then(Json::as_object).map(<[_]>::len),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn parses_escapes() {
+        let json = Json::parse(r#""a \"quote\", a \\ and a \/ then\n\tdone""#).unwrap();
+        assert_eq!(
+            json.as_str(),
+            Some("a \"quote\", a \\ and a / then\n\tdone")
+        );
+    }
+
+    #[test]
+    fn accessors_check_the_type() {
+        let json = Json::parse(r#"["1", 1]"#).unwrap();
+        let values = json.as_array().unwrap();
+        assert_eq!(values[0].as_f32(), None);
+        assert_eq!(values[1].as_str(), None);
+        assert!(values[1].get("key").is_none());
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        let malformed = [
+            "",
+            "{",
+            "[1, 2",
+            "[1, ]",
+            r#"{ "key" 1 }"#,
+            r#"{ key: 1 }"#,
+            r#"{ "a": 1 "b": 2 }"#,
+            r#""unterminated"#,
+            r#""\u0041""#,
+            "tru",
+            "nul",
+            "1.2.3",
+            "--1",
+            "[1] 2",
+        ];
+        for text in malformed {
+            assert!(Json::parse(text).is_err(), "accepted {:?}", text);
+        }
+    }
+
+    #[test]
+    fn errors_point_at_the_problem() {
+        let error = Json::parse("[1, ?]").err().unwrap();
+        assert_eq!(error, "unexpected character at character 4");
+    }
+}
@@ -3,23 +3,33 @@ use macroquad::audio as mq_audio;
 use macroquad::prelude as mq;
 use touch_button::TouchButton;
 
+mod affix;
 mod bullet;
+mod bullet_pattern;
 mod camera;
 mod colors;
+mod combo;
 mod consts;
 mod damage_number;
 mod deck;
+mod deck_edit;
 mod enemy;
 mod game_state;
 mod hand;
 mod hitbox;
 mod joystick;
+mod json;
 mod mouse;
+mod pickup;
 mod player;
 mod powerup;
+mod spatial_hash;
+mod stats;
+mod status_effect;
 mod timer;
 mod touch_button;
 mod util;
+mod wave_config;
 mod weapon;
 mod world;
 
@@ -37,6 +47,7 @@ struct TouchControls {
     movement_joystick: joystick::Joystick,
     aim_joystick: joystick::Joystick,
     start_pause_button: touch_button::TouchButton,
+    dash_button: touch_button::TouchButton,
     select_slot_buttons: Vec<touch_button::TouchButton>,
     fullscreen_button: touch_button::TouchButton,
 }
@@ -46,9 +57,10 @@ struct Resources {
     chess_texture: mq::Texture2D,
     font: mq::Font,
     music: mq_audio::Sound,
+    wave_config: wave_config::WaveConfig,
 }
 
-fn create_touch_controls(scale: f32) -> TouchControls {
+fn create_touch_controls(hand_size: usize, scale: f32) -> TouchControls {
     let joystick_height = consts::JOYSTICK_HEIGHT * mq::screen_height();
     let joystick_y = mq::screen_height() - joystick_height;
 
@@ -74,6 +86,15 @@ fn create_touch_controls(scale: f32) -> TouchControls {
         consts::PAUSE_BUTTON_WIDTH * mq::screen_width(),
         consts::PAUSE_BUTTON_HEIGHT * mq::screen_height(),
     ));
+    // right above the aim joystick
+    let dash_size = 2.0 * consts::DASH_BUTTON_RADIUS * scale;
+    let dash_margin = consts::DASH_BUTTON_MARGIN * scale;
+    let dash_button = touch_button::TouchButton::new(mq::Rect::new(
+        mq::screen_width() - dash_size - dash_margin,
+        joystick_y - dash_size - dash_margin,
+        dash_size,
+        dash_size,
+    ));
     let fullscreen_button = touch_button::TouchButton::new(mq::Rect::new(
         0.0,
         0.0,
@@ -81,16 +102,16 @@ fn create_touch_controls(scale: f32) -> TouchControls {
         mq::screen_height(),
     ));
 
-    let mut select_slot_buttons = Vec::with_capacity(consts::HAND_CARD_COUNT);
+    let mut select_slot_buttons = Vec::with_capacity(hand_size);
     let hand::HandDrawDimensions {
         mut x,
         y,
         card_width,
         card_height,
         spacing,
-    } = hand::Hand::hand_draw_dimensions(scale);
+    } = hand::Hand::hand_draw_dimensions(hand_size, scale);
 
-    for _ in 0..consts::HAND_CARD_COUNT {
+    for _ in 0..hand_size {
         select_slot_buttons.push(touch_button::TouchButton::new(mq::Rect::new(
             x,
             y,
@@ -104,6 +125,7 @@ fn create_touch_controls(scale: f32) -> TouchControls {
         movement_joystick,
         aim_joystick,
         start_pause_button,
+        dash_button,
         select_slot_buttons,
         fullscreen_button,
     }
@@ -144,18 +166,35 @@ async fn create_resources() -> Resources {
     let cards_texture_fut = mq::load_texture(consts::CARDS_TEXTURE_PATH);
     let chess_texture_fut = mq::load_texture(consts::CHESS_TEXTURE_PATH);
 
-    let (music, cards_texture, chess_texture, _) = join!(music_fut, cards_texture_fut, chess_texture_fut, mq::next_frame());
+    let wave_config_fut = wave_config::WaveConfig::load();
+
+    let (music, cards_texture, chess_texture, wave_config, _) = join!(
+        music_fut,
+        cards_texture_fut,
+        chess_texture_fut,
+        wave_config_fut,
+        mq::next_frame()
+    );
 
     Resources {
         cards_texture: cards_texture.unwrap(),
         chess_texture: chess_texture.unwrap(),
         font,
         music: music.unwrap(),
+        wave_config,
     }
 }
 
 struct ExtraUIButtons {
     music: Option<TouchButton>,
+    deck_viewer: Option<TouchButton>,
+    auto_cycle: Option<TouchButton>,
+}
+
+enum ExtraUIButton {
+    Music,
+    DeckViewer,
+    AutoCycle,
 }
 
 enum LargeFont {
@@ -165,21 +204,18 @@ enum LargeFont {
 
 enum SmallText<'a> {
     Simple(&'a str),
-    Button(&'a str),
+    Button(&'a str, ExtraUIButton),
 }
 impl<'a> SmallText<'a> {
     fn as_str(&self) -> &str {
         match self {
             SmallText::Simple(text) => text,
-            SmallText::Button(text) => text,
+            SmallText::Button(text, _) => text,
         }
     }
 }
 
-fn draw_controls_screen(
-    font: &mq::Font,
-    scale: f32,
-) {
+fn draw_controls_screen(font: &mq::Font, game_mode: game_state::GameMode, scale: f32) {
     let large_text = "Controls";
 
     let large_y = {
@@ -208,25 +244,38 @@ fn draw_controls_screen(
         "MOVE: WASD, RMB, left joystick",
         "AIM: arrow keys, mouse, right joystick",
         "SHOOT: LMB, space, left joystick",
-        "CHANGE CARD: 1/2/3, scroll wheel, tap card",
+        "DASH: shift, tap dash button",
+        "CHANGE CARD: 1-6, scroll wheel, tap card",
         "SELECT POWERUP/CARD: 8/9/0, tap powerup",
         "SWAP/CARD: enter, tap swap button",
+        "FUSE ACTIVE CARD: F, tap fuse button",
         "DISCARD CARD: backspace, delete, tap discard button",
         "PAUSE: escape, P, tap top left corner",
         "UNPAUSE: escape, P, tap anywhere",
         "TOGGLE MUSIC: M or tap in pause menu",
+        "VIEW DECK: V or tap in pause menu",
         "TOGGLE AUTO-SHOOT: Q",
+        "TOGGLE AUTO-CYCLE CARDS: C or tap in pause menu",
         "RESTART: R, tap anywhere",
         "",
+        "CHANGE GAME MODE: G, tap top left corner",
         "START GAME: space, enter, LMB, tap anywhere",
     ];
+    let game_mode_text = format!("GAME MODE: {}", game_mode.name());
 
     {
         let font_size = (scale * consts::SMALL_FONT_SIZE).round() as u16;
         let mut y = large_y + scale * consts::LARGE_FONT_SIZE;
         let first_text = small_texts[0];
         let text_height = mq::measure_text(first_text, Some(font), font_size, 1.0).height;
-        for small_text in small_texts.iter() {
+        let texts = small_texts
+            .iter()
+            .map(|text| (*text, colors::NORD4))
+            .chain(std::iter::once((
+                game_mode_text.as_str(),
+                consts::CONTROLS_GAME_MODE_COLOR,
+            )));
+        for (small_text, color) in texts {
             let text_dims = mq::measure_text(small_text, Some(font), font_size, 1.0);
 
             let x = mq::screen_width() / 2.0 - text_dims.width / 2.0;
@@ -239,7 +288,7 @@ fn draw_controls_screen(
                 mq::TextParams {
                     font: Some(font),
                     font_size,
-                    color: colors::NORD4,
+                    color,
                     ..Default::default()
                 },
             );
@@ -257,7 +306,11 @@ fn draw_overlay(
     large_font: LargeFont,
     scale: f32,
 ) -> ExtraUIButtons {
-    let mut extra_ui_buttons = ExtraUIButtons { music: None };
+    let mut extra_ui_buttons = ExtraUIButtons {
+        music: None,
+        deck_viewer: None,
+        auto_cycle: None,
+    };
 
     mq::draw_rectangle(
         0.0,
@@ -309,7 +362,7 @@ fn draw_overlay(
             let x = mq::screen_width() / 2.0 - text_dims.width / 2.0;
             y -= text_height / 2.0;
 
-            if let SmallText::Button(_) = small_text {
+            if let SmallText::Button(_, extra_ui_button) = small_text {
                 // Rect::new(X, Y - dimensions.offset_y, dimensions.width, dimensions.height)
 
                 let button_width =
@@ -330,12 +383,17 @@ fn draw_overlay(
                     colors::NORD14_BIG_ALPHA,
                 );
 
-                extra_ui_buttons.music = Some(TouchButton::new(mq::Rect::new(
+                let touch_button = Some(TouchButton::new(mq::Rect::new(
                     button_x,
                     button_y,
                     button_width,
                     button_height,
                 )));
+                match extra_ui_button {
+                    ExtraUIButton::Music => extra_ui_buttons.music = touch_button,
+                    ExtraUIButton::DeckViewer => extra_ui_buttons.deck_viewer = touch_button,
+                    ExtraUIButton::AutoCycle => extra_ui_buttons.auto_cycle = touch_button,
+                }
             }
 
             mq::draw_text_ex(
@@ -362,6 +420,8 @@ fn draw_overlay(
 
 struct Continuity {
     play_music: bool,
+    auto_cycle: bool,
+    game_mode: game_state::GameMode,
 }
 
 async fn play(resources: &Resources, continuity: &mut Continuity) {
@@ -382,15 +442,19 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
     let mut world = world::World::new();
     world.update_locations_to_build(&camera, consts::WINDOW_START_SIZE as f32);
 
-    let mut enemy_manager = enemy::EnemyManager::new();
+    let mut enemy_manager = enemy::EnemyManager::new(&resources.wave_config);
 
     let mut deck = deck::Deck::new();
-    let hand = hand::Hand::new(&mut deck);
+    let mut hand = hand::Hand::new(&mut deck);
+    hand.auto_cycle = continuity.auto_cycle;
+    hand.game_mode = continuity.game_mode;
     let mut player = player::Player::new(hand);
 
     let mut powerup_choices = powerup::Powerup::pick_three();
 
-    let mut card_choices = deck.draw_three_cards();
+    let mut deck_edit_choices = Vec::new();
+
+    let mut card_choices = Vec::new();
     let mut selected_card_choice = 0;
 
     let mut need_click_after = 0.0;
@@ -399,6 +463,8 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
 
     let mut damage_numbers: Vec<damage_number::DamageNumber> = Vec::new();
 
+    let mut pickups: Vec<pickup::Pickup> = Vec::new();
+
     mq_audio::stop_sound(&resources.music);
     mq_audio::play_sound(
         &resources.music,
@@ -420,7 +486,7 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
 
     let scale = mq::screen_width().min(mq::screen_height());
 
-    let mut touch_controls = create_touch_controls(scale);
+    let mut touch_controls = create_touch_controls(player.hand.size(), scale);
 
     let mut controls_screen = true;
 
@@ -433,13 +499,21 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
         let touches = mq::touches();
 
         if controls_screen {
+            let game_mode_pressed_id = touch_controls.start_pause_button.touched_down(&touches);
+            if mq::is_key_pressed(mq::KeyCode::G) || game_mode_pressed_id.is_some() {
+                continuity.game_mode.toggle();
+                player.hand.game_mode = continuity.game_mode;
+            }
             if mq::is_key_pressed(mq::KeyCode::Space)
                 || mq::is_key_pressed(mq::KeyCode::Enter)
                 || mq::is_mouse_button_pressed(mq::MouseButton::Left)
-                || touch_controls.fullscreen_button.touched_selected(&touches) {
+                || touch_controls
+                    .fullscreen_button
+                    .touched_selected_not_used(&touches, game_mode_pressed_id)
+            {
                 controls_screen = false;
             }
-            draw_controls_screen(&resources.font, scale);
+            draw_controls_screen(&resources.font, continuity.game_mode, scale);
             mq::next_frame().await;
             continue;
         }
@@ -467,8 +541,9 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
             old_height = mq::screen_height();
 
             should_update_locations_to_build = true;
-
-            touch_controls = create_touch_controls(scale);
+        }
+        if resized || touch_controls.select_slot_buttons.len() != player.hand.size() {
+            touch_controls = create_touch_controls(player.hand.size(), scale);
         }
 
         mouse_info.update(time_counter, delta);
@@ -482,9 +557,15 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
             mq::KeyCode::Key1,
             mq::KeyCode::Key2,
             mq::KeyCode::Key3,
+            mq::KeyCode::Key4,
+            mq::KeyCode::Key5,
+            mq::KeyCode::Key6,
         ];
         let scroll_wheel_target = if mouse_info.mouse_wheel_y() != 0 {
-            Some(((player.hand.active as i32 + mouse_info.mouse_wheel_y()).rem_euclid(consts::HAND_CARD_COUNT as i32)) as usize)
+            Some(
+                ((player.hand.active as i32 + mouse_info.mouse_wheel_y())
+                    .rem_euclid(player.hand.size() as i32)) as usize,
+            )
         } else {
             None
         };
@@ -519,6 +600,7 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
         let aim_joystick_result = touch_controls
             .aim_joystick
             .update(&touches, &used_touch_ids);
+        let dash_pressed = touch_controls.dash_button.touched_down(&touches).is_some();
         //----------------------------------------------------------------------------//
         if game_state.current_state() == game_state::GameState::Alive {
             let player_shot = player.handle_input(player::PlayerInputInfo {
@@ -526,6 +608,7 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
                 movement_joystick_result,
                 aim_joystick_result,
                 auto_shoot,
+                dash_pressed,
                 scale,
                 delta,
             });
@@ -537,42 +620,78 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
 
             if let util::Shot(true) = player_shot {
                 let weapon = player.hand.active_weapon();
-                let card = player.hand.active_card();
-                let hp = match card.suit {
-                    deck::Suit::Diamonds => powerups.diamonds_bullet_hp(),
-                    _ => 1,
-                };
-                let bullet = bullet::Bullet::new(
-                    player.pos,
-                    player.direction,
-                    weapon.bullet_speed,
-                    weapon.range,
-                    bullet::BulletDamage::Card(card),
-                    hp,
-                );
-                player_bullets.push(bullet);
+                let card = player.hand.active_effective_card();
+                let bullet_damage = player.hand.active_bullet_damage();
+                let hp = 1
+                    + player.stats.pierce(card.suit)
+                    + card.extra_pierce()
+                    + weapon.pattern.extra_pierce();
+                for direction in weapon.pattern.directions(player.direction) {
+                    let bullet = bullet::Bullet::new(
+                        player.pos,
+                        direction,
+                        weapon.bullet_speed,
+                        weapon.range,
+                        bullet_damage,
+                        hp,
+                    )
+                    .with_size(consts::BULLET_SIZE * weapon.pattern.size_mod())
+                    .with_homing(weapon.pattern.homing())
+                    .with_damage_mod(weapon.pattern.damage_mod());
+                    player_bullets.push(bullet);
+                }
+
+                if player.hand.active_card_broken() {
+                    player.hand.break_active_card(&mut deck);
+                } else if player.hand.active_card_out_of_ammo() {
+                    player.hand.refill_active_card(&mut deck);
+                }
             }
 
-            player_bullets
-                .iter_mut()
-                .for_each(|bullet| bullet.update(delta));
+            for bullet in player_bullets.iter_mut() {
+                bullet.update_homing(enemy_manager.enemies.iter().map(|enemy| enemy.pos), delta);
+                bullet.update(delta);
+            }
 
+            let mut reflected_bullets = Vec::new();
             'bullet: for bullet in player_bullets.iter_mut() {
                 for enemy in enemy_manager.enemies.iter_mut() {
                     if bullet.not_already_hit(enemy.id)
                         && hitbox::circles_collide(enemy, bullet)
                     {
+                        if enemy.block_hit() {
+                            damage_numbers.push(damage_number::DamageNumber::new(
+                                "blocked".to_owned(),
+                                consts::DAMAGE_NUMBER_TIME,
+                                enemy.pos,
+                                damage_number::DamageNumberColor::EnemyDamage,
+                            ));
+                            bullet.hit(enemy.id);
+                            continue 'bullet;
+                        }
+                        if let Some(reflect_damage) = enemy.try_reflect() {
+                            reflected_bullets.push(bullet.reflected(enemy.pos, reflect_damage));
+                            bullet.remove();
+                            continue 'bullet;
+                        }
+
                         let bullet::BulletHitResult {
                             damage,
-                            stun_time,
+                            status_effects,
                             heal_amount,
-                        } = bullet.hit_result(&powerups);
+                            kill_xp,
+                        } = bullet.hit_result(&player.stats);
+                        let damage = damage * enemy.status_effects.damage_taken_mod();
 
+                        let was_alive = enemy.health > 0.0;
                         enemy.health -= damage;
+                        if was_alive && enemy.health <= 0.0 {
+                            player.xp += kill_xp;
+                        }
                         let enemy_dn_text = if damage.is_infinite() {
                             "∞".to_owned()
                         } else {
-                            format!("{}", damage).to_owned()
+                            format!("{}", (damage * 10.0).round() / 10.0)
                         };
                         damage_numbers.push(damage_number::DamageNumber::new(
                             enemy_dn_text,
@@ -580,14 +699,16 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
                             enemy.pos,
                             damage_number::DamageNumberColor::EnemyDamage,
                         ));
-                        enemy.enemy_stunned.time_remaining += stun_time;
+                        for status_effect in status_effects {
+                            enemy.status_effects.apply(status_effect);
+                        }
 
                         player.health += heal_amount;
-                        player.health = player.health.min(player.max_health);
+                        player.health = player.health.min(player.stats.max_health());
 
                         if heal_amount > 0.0 {
                             damage_numbers.push(damage_number::DamageNumber::new(
-                                format!("+{}", heal_amount).to_owned(),
+                                format!("+{}", heal_amount),
                                 consts::DAMAGE_NUMBER_TIME,
                                 player.pos,
                                 damage_number::DamageNumberColor::PlayerHeal,
@@ -599,21 +720,47 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
                     }
                 }
             }
-            
+            enemy_manager.add_enemy_bullets(reflected_bullets);
 
             player_bullets.retain(bullet::Bullet::should_keep);
 
-            let max_dist = mq::Vec2::new(mq::screen_width(), mq::screen_height()).length();
-            let tile_size = scale / consts::TILES_PER_SCALE as f32;
-            let max_tiles = max_dist / tile_size;
-            let (enemies_killed, new_damage_numbers) = enemy_manager.update(&mut player, max_tiles, delta);
+            let (enemies_killed, new_damage_numbers) =
+                enemy_manager.update(&mut player, camera.view_rect(scale), delta);
             score += enemies_killed.count;
 
             damage_numbers.extend(new_damage_numbers);
             damage_numbers.iter_mut().for_each(|dn| dn.update(delta));
             damage_numbers.retain(damage_number::DamageNumber::should_keep);
 
-            player.xp += enemies_killed.count;
+            pickups.extend(enemies_killed.drops);
+            pickups
+                .iter_mut()
+                .for_each(|pickup| pickup.update(&player, delta));
+            pickups.retain(pickup::Pickup::should_keep);
+
+            let mut card_picked_up = false;
+            pickups.retain(|pickup| {
+                if !hitbox::circles_collide(pickup, &player) {
+                    return true;
+                }
+                match pickup.pickup_type {
+                    pickup::PickupType::Xp(xp) => player.xp += xp,
+                    pickup::PickupType::Health(heal_amount) => {
+                        player.health =
+                            (player.health + heal_amount).min(player.stats.max_health());
+                        damage_numbers.push(damage_number::DamageNumber::new(
+                            format!("+{}", heal_amount),
+                            consts::DAMAGE_NUMBER_TIME,
+                            player.pos,
+                            damage_number::DamageNumberColor::PlayerHeal,
+                        ));
+                    }
+                    pickup::PickupType::Card => card_picked_up = true,
+                    pickup::PickupType::Chip(chip_score) => score += chip_score,
+                }
+                false
+            });
+
             if player.xp >= consts::XP_PER_LEVEL(player.level) {
                 player.xp -= consts::XP_PER_LEVEL(player.level);
                 player.level += 1;
@@ -622,12 +769,27 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
                 card_choices = deck.draw_three_cards();
                 need_click_after = time_counter;
                 player.xp_bar_ratio = 1.0;
+
+                if player.level % consts::DECK_EDIT_LEVEL_PERIOD == 0 {
+                    game_state.next(game_state::GameState::DeckEdit);
+                    deck_edit_choices = deck_edit::DeckEdit::pick_three(&deck, &player);
+                }
             }
-            if enemies_killed.super_killed {
+            if enemies_killed.super_killed || card_picked_up {
                 game_state.next(game_state::GameState::PowerupCard);
                 powerup_choices = powerup::Powerup::pick_three();
                 need_click_after = time_counter;
             }
+            if enemies_killed.boss_killed {
+                // a card buff, a deck edit and a full heal
+                game_state.next(game_state::GameState::PowerupCard);
+                powerup_choices = powerup::Powerup::pick_three();
+                game_state.next(game_state::GameState::DeckEdit);
+                deck_edit_choices = deck_edit::DeckEdit::pick_three(&deck, &player);
+                need_click_after = time_counter;
+
+                player.health = player.stats.max_health();
+            }
 
             if player.health <= 0.0 {
                 game_state.next(game_state::GameState::Dead);
@@ -643,6 +805,9 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
 
         //----------------------------------------------------------------------------//
         world.draw(&camera, scale);
+        for pickup in pickups.iter() {
+            pickup.draw(&camera, scale);
+        }
         player.draw(&camera, &resources.chess_texture, scale);
         enemy_manager.draw(&camera, &resources.chess_texture, scale);
         for bullet in player_bullets.iter() {
@@ -651,7 +816,7 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
         for damage_number in damage_numbers.iter() {
             damage_number.draw(&camera, &resources.font, scale);
         }
-        enemy_manager.draw_hp_bars(&camera, scale);
+        enemy_manager.draw_hp_bars(&camera, &resources.font, scale);
         player.draw_bars(&resources.font, scale);
         let hand_top_y = player.hand.draw(&resources.cards_texture, &resources.font, scale);
         powerups.draw(&resources.cards_texture, scale);
@@ -662,6 +827,10 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
         if game_state.current_state() == game_state::GameState::Alive {
             touch_controls.movement_joystick.draw(is_mobile, scale);
             touch_controls.aim_joystick.draw(is_mobile, scale);
+            if is_mobile {
+                let dash_rect = touch_controls.dash_button.rect;
+                player.draw_dash_indicator(dash_rect.center(), dash_rect.w / 2.0, scale);
+            }
         } else {
             touch_controls.movement_joystick.reset();
             touch_controls.aim_joystick.reset();
@@ -711,8 +880,13 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
 
             y - text_dims.offset_y + text_dims.height
         };
+        enemy_manager.draw_boss_bar(&resources.font, score_text_bottom_y, scale);
 
-        let mut extra_ui_buttons = ExtraUIButtons { music: None };
+        let mut extra_ui_buttons = ExtraUIButtons {
+            music: None,
+            deck_viewer: None,
+            auto_cycle: None,
+        };
         if game_state.current_state() == game_state::GameState::Dead {
             player.update_bar_ratios(delta);
 
@@ -734,13 +908,20 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
         } else if game_state.current_state() == game_state::GameState::Paused {
             let small_texts = if is_mobile {
                 let music_button = if continuity.play_music {
-                    SmallText::Button("Music: on")
+                    SmallText::Button("Music: on", ExtraUIButton::Music)
+                } else {
+                    SmallText::Button("Music: off", ExtraUIButton::Music)
+                };
+                let auto_cycle_button = if continuity.auto_cycle {
+                    SmallText::Button("Auto cycle: on", ExtraUIButton::AutoCycle)
                 } else {
-                    SmallText::Button("Music: off")
+                    SmallText::Button("Auto cycle: off", ExtraUIButton::AutoCycle)
                 };
                 vec![
                     SmallText::Simple("Touch the screen to unpause"),
                     music_button,
+                    auto_cycle_button,
+                    SmallText::Button("View deck", ExtraUIButton::DeckViewer),
                 ]
             } else {
                 let auto_shoot_text = SmallText::Simple(if auto_shoot {
@@ -753,10 +934,17 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
                 } else {
                     "Music: off"
                 });
+                let auto_cycle_text = SmallText::Simple(if continuity.auto_cycle {
+                    "Auto cycle: on"
+                } else {
+                    "Auto cycle: off"
+                });
                 vec![
                     SmallText::Simple("Press Esc to unpause"),
                     auto_shoot_text,
                     music_text,
+                    auto_cycle_text,
+                    SmallText::Simple("Press V to view deck"),
                 ]
             };
             extra_ui_buttons = draw_overlay(
@@ -767,6 +955,8 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
                 LargeFont::Static,
                 scale,
             );
+        } else if game_state.current_state() == game_state::GameState::DeckViewer {
+            deck::draw_deck_viewer(&deck, &resources.cards_texture, &resources.font, scale);
         } else if game_state.current_state == game_state::GameState::PowerupCard {
             player.update_bar_ratios(delta);
 
@@ -789,8 +979,40 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
             }
 
             if let Some(powerup) = selected_powerup {
-                powerups.add(powerup);
+                powerups.add(powerup, &mut player.stats);
+                game_state.back();
+                // rewards can stack up (ex: a boss killed on a level up)
+                if game_state.current_state() == game_state::GameState::PowerupCard {
+                    powerup_choices = powerup::Powerup::pick_three();
+                }
+            }
+        } else if game_state.current_state == game_state::GameState::DeckEdit {
+            player.update_bar_ratios(delta);
+
+            let deck_edit_rects = powerup::draw_powerup_choices(
+                &deck_edit_choices,
+                &resources.font,
+                score_text_bottom_y,
+                hand_top_y,
+                scale,
+            );
+
+            let mut selected_deck_edit = None;
+            let keys = [mq::KeyCode::Key8, mq::KeyCode::Key9, mq::KeyCode::Key0];
+            for (i, (key, rect)) in keys.iter().zip(deck_edit_rects.iter()).enumerate() {
+                if mq::is_key_pressed(*key)
+                    || util::clicked_on(*rect, need_click_after, &mouse_info, true)
+                {
+                    selected_deck_edit = Some(deck_edit_choices[i]);
+                }
+            }
+
+            if let Some(deck_edit) = selected_deck_edit {
+                deck_edit.apply(&mut deck, &mut player);
                 game_state.back();
+                if game_state.current_state() == game_state::GameState::DeckEdit {
+                    deck_edit_choices = deck_edit::DeckEdit::pick_three(&deck, &player);
+                }
             }
         } else if game_state.current_state == game_state::GameState::ChooseCard {
             player.update_bar_ratios(delta);
@@ -800,6 +1022,7 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
                 &resources.cards_texture,
                 &resources.font,
                 selected_card_choice,
+                player.hand.can_fuse(),
                 score_text_bottom_y,
                 hand_top_y,
                 scale,
@@ -826,7 +1049,28 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
                     true,
                 )
             {
-                player.hand.set_card(card_choices[selected_card_choice]);
+                player
+                    .hand
+                    .set_card(card_choices[selected_card_choice], &mut deck);
+                for (i, card) in card_choices.iter().enumerate() {
+                    if i != selected_card_choice {
+                        deck.discard(*card);
+                    }
+                }
+                game_state.back();
+            } else if player.hand.can_fuse()
+                && (mq::is_key_pressed(mq::KeyCode::F)
+                    || util::clicked_on(
+                        card_choices_button_rects.fuse_button,
+                        need_click_after,
+                        &mouse_info,
+                        true,
+                    ))
+            {
+                player.hand.fuse(&mut deck);
+                for card in card_choices.iter() {
+                    deck.discard(*card);
+                }
                 game_state.back();
             } else if mq::is_key_pressed(mq::KeyCode::Backspace)
                 || mq::is_key_pressed(mq::KeyCode::Delete)
@@ -837,6 +1081,9 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
                     true,
                 )
             {
+                for card in card_choices.iter() {
+                    deck.discard(*card);
+                }
                 game_state.back();
             }
         }
@@ -844,9 +1091,20 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
         if mq::is_key_pressed(mq::KeyCode::Q) {
             auto_shoot = !auto_shoot;
         }
-        let music_toggle_pressed_id = extra_ui_buttons.music.and_then(|mut button| button.touched_down(&touches));
-        if mq::is_key_pressed(mq::KeyCode::M) || music_toggle_pressed_id.is_some()
-        {
+        let music_toggle_pressed_id = extra_ui_buttons
+            .music
+            .and_then(|mut button| button.touched_down(&touches));
+        let deck_viewer_pressed_id = extra_ui_buttons
+            .deck_viewer
+            .and_then(|mut button| button.touched_down(&touches));
+        let auto_cycle_pressed_id = extra_ui_buttons
+            .auto_cycle
+            .and_then(|mut button| button.touched_down(&touches));
+        if mq::is_key_pressed(mq::KeyCode::C) || auto_cycle_pressed_id.is_some() {
+            continuity.auto_cycle = !continuity.auto_cycle;
+            player.hand.auto_cycle = continuity.auto_cycle;
+        }
+        if mq::is_key_pressed(mq::KeyCode::M) || music_toggle_pressed_id.is_some() {
             continuity.play_music = !continuity.play_music;
             if continuity.play_music {
                 mq_audio::set_sound_volume(&resources.music, 1.0);
@@ -854,10 +1112,25 @@ async fn play(resources: &Resources, continuity: &mut Continuity) {
                 mq_audio::set_sound_volume(&resources.music, 0.0);
             }
         }
-        if mq::is_key_pressed(mq::KeyCode::Escape)
+        if game_state.current_state() == game_state::GameState::DeckViewer {
+            if mq::is_key_pressed(mq::KeyCode::Escape)
+                || mq::is_key_pressed(mq::KeyCode::P)
+                || mq::is_key_pressed(mq::KeyCode::V)
+                || touch_controls.fullscreen_button.touched_selected(&touches)
+            {
+                game_state.back();
+            }
+        } else if game_state.current_state() == game_state::GameState::Paused
+            && (mq::is_key_pressed(mq::KeyCode::V) || deck_viewer_pressed_id.is_some())
+        {
+            game_state.next(game_state::GameState::DeckViewer);
+        } else if mq::is_key_pressed(mq::KeyCode::Escape)
             || mq::is_key_pressed(mq::KeyCode::P)
             || (game_state.current_state() == game_state::GameState::Paused
-                && touch_controls.fullscreen_button.touched_selected_not_used(&touches, music_toggle_pressed_id))
+                && touch_controls.fullscreen_button.touched_selected_not_used(
+                    &touches,
+                    music_toggle_pressed_id.or(auto_cycle_pressed_id),
+                ))
             || (game_state.current_state() == game_state::GameState::Alive
                 && touch_controls.start_pause_button.touched_down(&touches).is_some())
         {
@@ -874,7 +1147,11 @@ async fn main() {
     mq::rand::srand(instant::now() as u64);
 
     let resources = create_resources().await;
-    let mut continuity = Continuity { play_music: true };
+    let mut continuity = Continuity {
+        play_music: true,
+        auto_cycle: false,
+        game_mode: game_state::GameMode::Classic,
+    };
 
     loop {
         play(&resources, &mut continuity).await;
@@ -0,0 +1,134 @@
+use macroquad::prelude as mq;
+
+use crate::{camera, colors, consts, hitbox, player};
+
+#[derive(Clone, Copy)]
+pub enum PickupType {
+    Xp(i32),     // amount
+    Health(f32), // amount
+    Card,        // a card buff choice
+    Chip(i32),   // score
+}
+
+pub struct Pickup {
+    pos: mq::Vec2, // tiles
+    pub pickup_type: PickupType,
+    magnetized: bool, // flying towards the player
+    time_left: f32,   // seconds, despawns at 0
+}
+
+impl Pickup {
+    pub fn new(pos: mq::Vec2, pickup_type: PickupType) -> Self {
+        Self {
+            pos,
+            pickup_type,
+            magnetized: false,
+            time_left: consts::PICKUP_LIFETIME,
+        }
+    }
+
+    pub fn update(&mut self, player: &player::Player, delta: f32) {
+        let vec_to_player = player.pos - self.pos;
+        if vec_to_player.length() < player.stats.pickup_radius() {
+            self.magnetized = true;
+        }
+        if self.magnetized {
+            // already on its way, so never despawn
+            self.time_left = consts::PICKUP_LIFETIME;
+            let step = (consts::PICKUP_MAGNET_SPEED * delta).min(vec_to_player.length());
+            self.pos += vec_to_player.normalize_or_zero() * step;
+        } else {
+            self.time_left -= delta;
+        }
+    }
+
+    pub fn should_keep(&self) -> bool {
+        self.time_left > 0.0
+    }
+
+    fn size(&self) -> f32 {
+        match self.pickup_type {
+            // bigger gems for more xp
+            PickupType::Xp(xp) => {
+                consts::PICKUP_XP_SIZE * (xp as f32).sqrt().min(consts::PICKUP_XP_MAX_SIZE_MOD)
+            }
+            PickupType::Health(_) => consts::PICKUP_HEALTH_SIZE,
+            PickupType::Card => consts::PICKUP_CARD_SIZE,
+            PickupType::Chip(_) => consts::PICKUP_CHIP_SIZE,
+        }
+    }
+
+    pub fn draw(&self, camera: &camera::Camera, scale: f32) {
+        let draw_pos = (self.pos - camera.pos) * scale / consts::TILES_PER_SCALE as f32
+            + mq::Vec2::new(mq::screen_width() / 2.0, mq::screen_height() / 2.0);
+        let size = self.size() * scale;
+
+        // blink before despawning
+        if self.time_left < consts::PICKUP_BLINK_TIME
+            && (self.time_left / consts::PICKUP_BLINK_PERIOD) as i32 % 2 == 1
+        {
+            return;
+        }
+
+        match self.pickup_type {
+            PickupType::Xp(_) => {
+                mq::draw_poly(draw_pos.x, draw_pos.y, 4, size, 0.0, colors::NORD15);
+            }
+            PickupType::Health(_) => {
+                // a plus sign
+                mq::draw_rectangle(
+                    draw_pos.x - size,
+                    draw_pos.y - size / 3.0,
+                    size * 2.0,
+                    size * 2.0 / 3.0,
+                    colors::NORD11,
+                );
+                mq::draw_rectangle(
+                    draw_pos.x - size / 3.0,
+                    draw_pos.y - size,
+                    size * 2.0 / 3.0,
+                    size * 2.0,
+                    colors::NORD11,
+                );
+            }
+            PickupType::Card => {
+                let width = size * consts::CARD_PX_WIDTH / consts::CARD_PX_HEIGHT;
+                mq::draw_rectangle(
+                    draw_pos.x - width,
+                    draw_pos.y - size,
+                    width * 2.0,
+                    size * 2.0,
+                    colors::NORD6,
+                );
+                mq::draw_rectangle_lines(
+                    draw_pos.x - width,
+                    draw_pos.y - size,
+                    width * 2.0,
+                    size * 2.0,
+                    consts::PICKUP_OUTLINE_THICKNESS * scale,
+                    colors::NORD13,
+                );
+            }
+            PickupType::Chip(_) => {
+                mq::draw_circle(draw_pos.x, draw_pos.y, size, colors::NORD13);
+                mq::draw_circle_lines(
+                    draw_pos.x,
+                    draw_pos.y,
+                    size * 0.6,
+                    consts::PICKUP_OUTLINE_THICKNESS * scale,
+                    colors::NORD0,
+                );
+            }
+        }
+    }
+}
+
+impl hitbox::Circle for Pickup {
+    fn center(&self) -> mq::Vec2 {
+        self.pos
+    }
+
+    fn radius(&self) -> f32 {
+        self.size() * consts::TILES_PER_SCALE as f32
+    }
+}
@@ -1,12 +1,13 @@
 use macroquad::prelude as mq;
 
-use crate::{camera, colors, consts, hand, hitbox, joystick, mouse, util};
+use crate::{camera, colors, consts, hand, hitbox, joystick, mouse, stats, status_effect, util};
 
 pub struct PlayerInputInfo<'a> {
     pub mouse_info: &'a mut mouse::MouseInfo,
     pub movement_joystick_result: joystick::JoystickUpdateResult,
     pub aim_joystick_result: joystick::JoystickUpdateResult,
     pub auto_shoot: bool,
+    pub dash_pressed: bool, // from the touch button
     pub scale: f32,
     pub delta: f32,
 }
@@ -16,12 +17,18 @@ pub struct Player {
     pub direction: f32, // in radians
     pub hand: hand::Hand,
     pub health: f32,
-    pub max_health: f32,
+    pub stats: stats::Stats,
+    pub status_effects: status_effect::StatusEffects,
     pub xp: i32,
     pub level: i32,
     hp_bar_ratio: f32,
     pub xp_bar_ratio: f32,
     pub movement: mq::Vec2,
+    dash_direction: mq::Vec2,
+    dash_time: f32,      // seconds left in the current dash
+    dash_cooldown: f32,  // seconds until the next dash
+    iframe_time: f32,    // seconds of invulnerability left
+    knockback: mq::Vec2, // tiles per second
 }
 
 impl Player {
@@ -31,12 +38,18 @@ impl Player {
             direction: 0.0,
             hand,
             health: consts::PLAYER_MAX_HEALTH,
-            max_health: consts::PLAYER_MAX_HEALTH,
+            stats: stats::Stats::new(),
+            status_effects: status_effect::StatusEffects::new(),
             xp: 0,
             level: 1,
             hp_bar_ratio: 1.0,
             xp_bar_ratio: 0.0,
             movement: mq::Vec2::ZERO,
+            dash_direction: mq::Vec2::ZERO,
+            dash_time: 0.0,
+            dash_cooldown: 0.0,
+            iframe_time: 0.0,
+            knockback: mq::Vec2::ZERO,
         }
     }
 
@@ -46,10 +59,19 @@ impl Player {
             movement_joystick_result,
             aim_joystick_result,
             auto_shoot,
+            dash_pressed,
             scale,
             delta,
         } = player_info_info;
 
+        self.health -= self.status_effects.update(delta);
+        self.stats
+            .set_modifiers(stats::Source::Hand, self.hand.stat_modifiers());
+        self.stats.set_modifiers(
+            stats::Source::StatusEffect,
+            self.status_effects.stat_modifiers(),
+        );
+
         let movement = (if movement_joystick_result.active {
             movement_joystick_result.pos
         } else if mq::is_mouse_button_down(mq::MouseButton::Right) {
@@ -73,10 +95,38 @@ impl Player {
         })
         .normalize_or_zero();
 
-        let speed = consts::PLAYER_SPEED * delta * self.hand.get_ms_penalty();
-        self.pos += movement * speed;
+        self.dash_cooldown = (self.dash_cooldown - delta).max(0.0);
+        self.iframe_time = (self.iframe_time - delta).max(0.0);
+        if (mq::is_key_pressed(mq::KeyCode::LeftShift)
+            || mq::is_key_pressed(mq::KeyCode::RightShift)
+            || dash_pressed)
+            && self.dash_cooldown <= 0.0
+        {
+            // standing still dashes the way the player is facing
+            self.dash_direction = if movement == mq::Vec2::ZERO {
+                mq::Vec2::new(self.direction.cos(), self.direction.sin())
+            } else {
+                movement
+            };
+            self.dash_time = consts::PLAYER_DASH_TIME;
+            self.dash_cooldown = consts::PLAYER_DASH_COOLDOWN;
+            self.iframe_time = self.iframe_time.max(consts::PLAYER_DASH_IFRAME_TIME);
+        }
+
+        if self.dash_time > 0.0 {
+            // dashing isn't slowed down by shooting
+            let dash_step = self.dash_time.min(delta);
+            self.pos += self.dash_direction * consts::PLAYER_DASH_SPEED * dash_step;
+            self.dash_time -= dash_step;
+        } else {
+            let speed = self.stats.move_speed() * delta;
+            self.pos += movement * speed;
+        }
         self.movement = movement;
 
+        self.pos += self.knockback * delta;
+        self.knockback *= (-consts::PLAYER_HIT_KNOCKBACK_DECAY * delta).exp();
+
         let aim = (if aim_joystick_result.active {
             aim_joystick_result.pos
         } else {
@@ -106,9 +156,11 @@ impl Player {
             self.direction = movement.y.atan2(movement.x);
         }
 
+        self.health = (self.health + self.stats.regen() * delta).min(self.stats.max_health());
+
         self.update_bar_ratios(delta);
 
-        self.hand.update(delta);
+        self.hand.update(delta, self.stats.fire_rate());
         // uses short-circuiting to only `try_shoot` if the player is requesting to shoot
         // `.0` is used to get the `bool` from the `Shot` struct
         util::Shot(
@@ -120,10 +172,29 @@ impl Player {
         )
     }
 
+    pub fn is_invulnerable(&self) -> bool {
+        self.iframe_time > 0.0
+    }
+
+    // damage followed by a short grace period, pushed away from the source
+    // returns the damage taken after armor
+    pub fn take_hit(&mut self, damage: f32, source: mq::Vec2) -> f32 {
+        let damage = damage * (1.0 - self.stats.armor());
+        self.health -= damage;
+        self.iframe_time = self.iframe_time.max(consts::PLAYER_HIT_IFRAME_TIME);
+        self.knockback =
+            (self.pos - source).normalize_or_zero() * consts::PLAYER_HIT_KNOCKBACK_SPEED;
+        damage
+    }
+
+    fn dash_ready_ratio(&self) -> f32 {
+        1.0 - self.dash_cooldown / consts::PLAYER_DASH_COOLDOWN
+    }
+
     pub fn update_bar_ratios(&mut self, delta: f32) {
         {
             let old_ratio = self.hp_bar_ratio;
-            let target_ratio = self.health / self.max_health;
+            let target_ratio = self.health / self.stats.max_health();
             let dif = target_ratio - old_ratio;
             self.hp_bar_ratio += dif * delta * consts::BAR_UPDATE_SPEED;
         }
@@ -152,11 +223,20 @@ impl Player {
             texture_info.3 as f32,
         );
 
+        // flash while invulnerable
+        let flash_off = self.is_invulnerable()
+            && (self.iframe_time / consts::PLAYER_HIT_FLASH_PERIOD) as i32 % 2 == 1;
+        let tint = if flash_off {
+            mq::Color::new(1.0, 1.0, 1.0, consts::PLAYER_HIT_FLASH_ALPHA)
+        } else {
+            mq::WHITE
+        };
+
         mq::draw_texture_ex(
             chess_texture,
             draw_pos.x - player_size / 2.0,
             draw_pos.y - player_size / 2.0,
-            mq::WHITE,
+            tint,
             mq::DrawTextureParams {
                 dest_size: Some(mq::Vec2::splat(player_size)),
                 source: Some(texture_source),
@@ -164,6 +244,8 @@ impl Player {
             },
         );
 
+        self.status_effects.draw(draw_pos, player_size, scale);
+
         let triangle_side_length = player_size * 0.5;
         let triangle_height = triangle_side_length * 3.0_f32.sqrt() / 2.0;
 
@@ -194,6 +276,29 @@ impl Player {
         mq::draw_triangle(top_point, side_point_1, side_point_2, colors::NORD4);
     }
 
+    // fills up while the dash is on cooldown
+    pub fn draw_dash_indicator(&self, center: mq::Vec2, radius: f32, scale: f32) {
+        let ready_ratio = self.dash_ready_ratio();
+        mq::draw_circle(center.x, center.y, radius, colors::NORD6_ALPHA);
+        mq::draw_circle(
+            center.x,
+            center.y,
+            radius * ready_ratio,
+            if ready_ratio >= 1.0 {
+                colors::NORD13
+            } else {
+                colors::NORD3
+            },
+        );
+        mq::draw_circle_lines(
+            center.x,
+            center.y,
+            radius,
+            consts::PLAYER_DASH_INDICATOR_THICKNESS * scale,
+            colors::NORD6,
+        );
+    }
+
     pub fn draw_bars(&self, font: &mq::Font, scale: f32) {
         let bar_width = scale * consts::PLAYER_HP_BAR_WIDTH;
         let bar_height = scale * consts::PLAYER_HP_BAR_HEIGHT;
@@ -237,8 +342,19 @@ impl Player {
             },
         );
 
+        // dash cooldown, left of both bars
+        let bar_gap = scale * consts::PLAYER_XP_BAR_OFFSET;
+        self.draw_dash_indicator(
+            mq::Vec2::new(
+                x - scale * consts::PLAYER_DASH_INDICATOR_OFFSET - bar_height,
+                y - bar_gap / 2.0,
+            ),
+            bar_height,
+            scale,
+        );
+
         // HP bar
-        let y = y - bar_height - scale * consts::PLAYER_XP_BAR_OFFSET;
+        let y = y - bar_height - bar_gap;
         mq::draw_rectangle(x, y, bar_width, bar_height, colors::NORD6_ALPHA);
         mq::draw_rectangle(
             x,
@@ -253,7 +369,7 @@ impl Player {
         let text = format!(
             "{:.0} / {:.0}",
             self.health.round(),
-            self.max_health.round()
+            self.stats.max_health().round()
         );
         let text_dims = mq::measure_text(&text, Some(font), font_size, 1.0);
         let text_pos = mq::Vec2::new(
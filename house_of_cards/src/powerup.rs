@@ -1,6 +1,6 @@
 use macroquad::prelude as mq;
 
-use crate::{colors, consts, deck};
+use crate::{colors, consts, deck, stats};
 
 #[derive(PartialEq, Eq, Copy, Clone)]
 pub enum Powerup {
@@ -8,6 +8,13 @@ pub enum Powerup {
     Hearts,
     Clubs,
     Spades,
+    DiamondsBurn,
+    HeartsVulnerable,
+    ClubsFreeze,
+    ClubsSlow,
+    SpadesPoison,
+    HeartsRegen,
+    ClubsArmor,
 }
 
 impl Powerup {
@@ -27,11 +34,18 @@ impl Powerup {
 
     pub fn pick_card() -> Powerup {
         // random card powerup
-        match mq::rand::gen_range(0, 4) {
+        match mq::rand::gen_range(0, 11) {
             0 => Powerup::Diamonds,
             1 => Powerup::Hearts,
             2 => Powerup::Clubs,
             3 => Powerup::Spades,
+            4 => Powerup::DiamondsBurn,
+            5 => Powerup::HeartsVulnerable,
+            6 => Powerup::ClubsFreeze,
+            7 => Powerup::ClubsSlow,
+            8 => Powerup::SpadesPoison,
+            9 => Powerup::HeartsRegen,
+            10 => Powerup::ClubsArmor,
             _ => unreachable!(),
         }
     }
@@ -75,47 +89,134 @@ impl Powerup {
 
     fn suit(&self) -> deck::Suit {
         match self {
-            Powerup::Diamonds => deck::Suit::Diamonds,
-            Powerup::Hearts => deck::Suit::Hearts,
-            Powerup::Clubs => deck::Suit::Clubs,
-            Powerup::Spades => deck::Suit::Spades,
+            Powerup::Diamonds | Powerup::DiamondsBurn => deck::Suit::Diamonds,
+            Powerup::Hearts | Powerup::HeartsVulnerable | Powerup::HeartsRegen => {
+                deck::Suit::Hearts
+            }
+            Powerup::Clubs | Powerup::ClubsFreeze | Powerup::ClubsSlow | Powerup::ClubsArmor => {
+                deck::Suit::Clubs
+            }
+            Powerup::Spades | Powerup::SpadesPoison => deck::Suit::Spades,
+        }
+    }
+
+    // most buffs only count for shots from cards of their suit
+    fn modifier(&self) -> (stats::Scope, stats::Modifier) {
+        let suit = stats::Scope::Suit(self.suit());
+        match self {
+            Powerup::Diamonds => (
+                suit,
+                stats::Modifier::Add(stats::Stat::Pierce, consts::DIAMONDS_PIERCE),
+            ),
+            Powerup::Hearts => (
+                suit,
+                stats::Modifier::Add(stats::Stat::HealChance, consts::HEARTS_HEAL_CHANCE),
+            ),
+            Powerup::Clubs => (
+                suit,
+                stats::Modifier::Add(stats::Stat::StunTime, consts::CLUBS_STUN_TIME),
+            ),
+            Powerup::Spades => (
+                suit,
+                stats::Modifier::Add(stats::Stat::CritChance, consts::SPADES_CRIT_CHANCE),
+            ),
+            Powerup::DiamondsBurn => (
+                suit,
+                stats::Modifier::Add(stats::Stat::BurnDamage, consts::DIAMONDS_BURN_DAMAGE),
+            ),
+            Powerup::HeartsVulnerable => (
+                suit,
+                stats::Modifier::Add(stats::Stat::Vulnerable, consts::HEARTS_VULNERABLE_DAMAGE),
+            ),
+            Powerup::ClubsFreeze => (
+                suit,
+                stats::Modifier::Add(stats::Stat::FreezeChance, consts::CLUBS_FREEZE_CHANCE),
+            ),
+            Powerup::ClubsSlow => (
+                suit,
+                stats::Modifier::Mul(stats::Stat::SlowSpeed, consts::CLUBS_SLOW_SPEED_MOD),
+            ),
+            Powerup::SpadesPoison => (
+                suit,
+                stats::Modifier::Add(stats::Stat::PoisonStacks, consts::SPADES_POISON_STACKS),
+            ),
+            Powerup::HeartsRegen => (
+                stats::Scope::All,
+                stats::Modifier::Add(stats::Stat::Regen, consts::HEARTS_REGEN),
+            ),
+            Powerup::ClubsArmor => (
+                stats::Scope::All,
+                stats::Modifier::Add(stats::Stat::Armor, consts::CLUBS_ARMOR),
+            ),
         }
     }
+}
 
+impl Choice for Powerup {
     fn color(&self) -> mq::Color {
         match self {
             Powerup::Diamonds => colors::NORD11,
             Powerup::Hearts => colors::NORD14,
             Powerup::Clubs => colors::NORD12,
             Powerup::Spades => colors::NORD15,
+            Powerup::DiamondsBurn => colors::NORD13,
+            Powerup::HeartsVulnerable => colors::NORD11,
+            Powerup::ClubsFreeze => colors::NORD8,
+            Powerup::ClubsSlow => colors::NORD9,
+            Powerup::SpadesPoison => colors::NORD14,
+            Powerup::HeartsRegen => colors::NORD14,
+            Powerup::ClubsArmor => colors::NORD10,
         }
     }
 
-    fn color_light_version(&self) -> mq::Color {
-        let mut color = self.color();
-        color.r = (color.r + 0.3).min(1.0);
-        color.g = (color.g + 0.3).min(1.0);
-        color.b = (color.b + 0.3).min(1.0);
-
-        color
-    }
-
-    fn main_text(&self) -> Vec<&str> {
-        match self {
+    fn main_text(&self) -> Vec<String> {
+        let text = match self {
             Powerup::Diamonds => vec!["Diamonds:", "Pierce", "+1 Enemies"],
             Powerup::Hearts => vec!["Hearts:", "+2% chance", "to heal"],
             Powerup::Clubs => vec!["Clubs:", "+0.1s Stun"],
-            Powerup::Spades => vec!["Spades:", "+33% chance", "to double", "damage"],
-        }
+            Powerup::Spades => vec!["Spades:", "+33% crit", "chance"],
+            Powerup::DiamondsBurn => vec!["Diamonds:", "+2 burn", "damage/sec"],
+            Powerup::HeartsVulnerable => vec!["Hearts:", "+25% damage", "taken"],
+            Powerup::ClubsFreeze => vec!["Clubs:", "+10% chance", "to freeze"],
+            Powerup::ClubsSlow => vec!["Clubs:", "-20% enemy", "speed"],
+            Powerup::SpadesPoison => vec!["Spades:", "+1 poison", "stack"],
+            Powerup::HeartsRegen => vec!["Hearts:", "+0.1 hp/sec", "regen"],
+            Powerup::ClubsArmor => vec!["Clubs:", "+10% armor"],
+        };
+        text.into_iter().map(String::from).collect()
     }
 
-    fn sub_text(&self) -> Vec<&str> {
-        match self {
+    fn sub_text(&self) -> Vec<String> {
+        let text = match self {
             Powerup::Diamonds => vec!["bullets go through", "an additional enemy"],
             Powerup::Hearts => vec!["1 hp on hit"],
             Powerup::Clubs => vec!["on hit"],
-            Powerup::Spades => vec!["can stack"],
-        }
+            Powerup::Spades => vec!["crits do", "double damage"],
+            Powerup::DiamondsBurn => vec!["for 2s on hit"],
+            Powerup::HeartsVulnerable => vec!["for 2s on hit"],
+            Powerup::ClubsFreeze => vec!["for 1s on hit"],
+            Powerup::ClubsSlow => vec!["for 1.5s on hit"],
+            Powerup::SpadesPoison => vec!["on hit, stacks", "last 3s"],
+            Powerup::HeartsRegen => vec!["always active"],
+            Powerup::ClubsArmor => vec!["less damage taken", "always active"],
+        };
+        text.into_iter().map(String::from).collect()
+    }
+}
+
+// anything that can be picked on the powerup choice screen
+pub trait Choice {
+    fn color(&self) -> mq::Color;
+    fn main_text(&self) -> Vec<String>;
+    fn sub_text(&self) -> Vec<String>;
+
+    fn color_light_version(&self) -> mq::Color {
+        let mut color = self.color();
+        color.r = (color.r + 0.3).min(1.0);
+        color.g = (color.g + 0.3).min(1.0);
+        color.b = (color.b + 0.3).min(1.0);
+
+        color
     }
 }
 
@@ -136,49 +237,15 @@ impl Powerups {
         }
     }
 
-    pub fn add(&mut self, powerup: Powerup) {
+    pub fn add(&mut self, powerup: Powerup, stats: &mut stats::Stats) {
+        let (scope, modifier) = powerup.modifier();
+        stats.add_modifier(stats::Source::Powerup, scope, modifier);
         self.powerups.push(powerup);
     }
-
-    pub fn count(&self, powerup: &Powerup) -> usize {
-        self.powerups.iter().filter(|p| **p == *powerup).count()
-    }
-
-    pub fn diamonds_bullet_hp(&self) -> i32 {
-        self.count(&Powerup::Diamonds) as i32 + 1
-    }
-
-    pub fn hearts_heal_amount(&self) -> f32 {
-        let count = self.count(&Powerup::Hearts);
-        let mut amount = 0.0;
-        for _ in 0..count {
-            if mq::rand::gen_range(0.0, 1.0) < consts::HEARTS_HEAL_CHANCE {
-                amount += 1.0;
-            }
-        }
-
-        amount
-    }
-
-    pub fn clubs_stun_time(&self) -> f32 {
-        self.count(&Powerup::Clubs) as f32 * consts::CLUBS_STUN_TIME
-    }
-
-    pub fn spades_damage_mod(&self) -> f32 {
-        let count = self.count(&Powerup::Spades);
-        let mut modifier = 1.0;
-        for _ in 0..count {
-            if mq::rand::gen_range(0.0, 1.0) < consts::SPADES_DAMAGE_CHANCE {
-                modifier *= 2.0;
-            }
-        }
-
-        modifier
-    }
 }
 
-pub fn draw_powerup_choices(
-    powerup_choices: &[Powerup],
+pub fn draw_powerup_choices<T: Choice>(
+    powerup_choices: &[T],
     font: &mq::Font,
     score_text_bottom_y: f32,
     hand_top_y: f32,
@@ -230,7 +297,7 @@ pub fn draw_powerup_choices(
             let mut y = center.y - total_height;
 
             for i in 0..main_text.len() {
-                let text = main_text[i];
+                let text = &main_text[i];
                 let text_dims = text_dims_large[i];
                 let x = center.x - text_dims_large[i].width / 2.0;
 
@@ -262,7 +329,7 @@ pub fn draw_powerup_choices(
             let mut y = center.y;
 
             for i in 0..sub_text.len() {
-                let text = sub_text[i];
+                let text = &sub_text[i];
                 let text_dims = text_dims_small[i];
                 let x = center.x - text_dims_small[i].width / 2.0;
 
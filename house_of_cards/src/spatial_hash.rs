@@ -0,0 +1,38 @@
+use macroquad::prelude as mq;
+use std::collections::HashMap;
+
+// buckets indices by grid cell so nearby lookups don't have to check everything
+pub struct SpatialHash {
+    cell_size: f32, // tiles, should be at least the largest query distance
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    fn cell(&self, pos: mq::Vec2) -> (i32, i32) {
+        (
+            (pos.x / self.cell_size).floor() as i32,
+            (pos.y / self.cell_size).floor() as i32,
+        )
+    }
+
+    pub fn insert(&mut self, index: usize, pos: mq::Vec2) {
+        let cell = self.cell(pos);
+        self.cells.entry(cell).or_default().push(index);
+    }
+
+    // everything in the surrounding 3x3 cells (may include the queried index)
+    pub fn nearby(&self, pos: mq::Vec2) -> impl Iterator<Item = usize> + '_ {
+        let (cell_x, cell_y) = self.cell(pos);
+        (-1..=1)
+            .flat_map(move |dx| (-1..=1).map(move |dy| (cell_x + dx, cell_y + dy)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flat_map(|indices| indices.iter().copied())
+    }
+}
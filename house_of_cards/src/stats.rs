@@ -0,0 +1,342 @@
+use macroquad::prelude as mq;
+
+use crate::{consts, deck, status_effect};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Stat {
+    MoveSpeed,    // tiles per second
+    MaxHealth,    // hp
+    FireRate,     // multiplier
+    Damage,       // multiplier
+    CritChance,   // percent
+    PickupRadius, // tiles
+    Armor,        // percent of damage blocked, negative takes extra damage
+    Regen,        // hp per second
+
+    // on hit effects
+    Pierce,       // extra enemies
+    HealChance,   // percent
+    StunTime,     // seconds
+    FreezeChance, // percent
+    SlowSpeed,    // enemy speed multiplier
+    BurnDamage,   // per second
+    Vulnerable,   // extra damage taken
+    PoisonStacks, // stacks
+}
+
+impl Stat {
+    fn base(&self) -> f32 {
+        match self {
+            Stat::MoveSpeed => consts::PLAYER_SPEED,
+            Stat::MaxHealth => consts::PLAYER_MAX_HEALTH,
+            Stat::FireRate => 1.0,
+            Stat::Damage => 1.0,
+            Stat::CritChance => consts::PLAYER_CRIT_CHANCE,
+            Stat::PickupRadius => consts::PICKUP_MAGNET_RADIUS,
+            Stat::Armor => consts::PLAYER_ARMOR,
+            Stat::Regen => consts::PLAYER_REGEN,
+            Stat::SlowSpeed => 1.0,
+            Stat::Pierce
+            | Stat::HealChance
+            | Stat::StunTime
+            | Stat::FreezeChance
+            | Stat::BurnDamage
+            | Stat::Vulnerable
+            | Stat::PoisonStacks => 0.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Modifier {
+    Add(Stat, f32), // added to the base
+    Mul(Stat, f32), // multiplies the base plus every addition
+}
+
+// which shots a modifier counts for
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    All,
+    Suit(deck::Suit), // only shots from cards of this suit
+}
+
+// where modifiers came from, so a source can replace its own
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Hand,         // combo bonuses and the active weapon, refreshed every frame
+    DeckEdit,     // upgrades for the rest of the run
+    Powerup,      // card buffs for the rest of the run
+    StatusEffect, // effects on the player, refreshed every frame
+}
+
+pub struct Stats {
+    modifiers: Vec<(Source, Scope, Modifier)>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self {
+            modifiers: Vec::new(),
+        }
+    }
+
+    pub fn add_modifier(&mut self, source: Source, scope: Scope, modifier: Modifier) {
+        self.modifiers.push((source, scope, modifier));
+    }
+
+    pub fn set_modifiers(&mut self, source: Source, modifiers: Vec<Modifier>) {
+        self.modifiers.retain(|(s, _, _)| *s != source);
+        self.modifiers.extend(
+            modifiers
+                .into_iter()
+                .map(|modifier| (source, Scope::All, modifier)),
+        );
+    }
+
+    // a suit also counts the modifiers for that suit
+    fn resolve(&self, stat: Stat, suit: Option<deck::Suit>) -> f32 {
+        let mut add = 0.0;
+        let mut mul = 1.0;
+        for (_, scope, modifier) in self.modifiers.iter() {
+            let in_scope = match scope {
+                Scope::All => true,
+                Scope::Suit(scope_suit) => Some(*scope_suit) == suit,
+            };
+            if !in_scope {
+                continue;
+            }
+            match *modifier {
+                Modifier::Add(s, value) if s == stat => add += value,
+                Modifier::Mul(s, value) if s == stat => mul *= value,
+                _ => {}
+            }
+        }
+        (stat.base() + add) * mul
+    }
+
+    pub fn get(&self, stat: Stat) -> f32 {
+        self.resolve(stat, None)
+    }
+
+    pub fn get_for(&self, stat: Stat, suit: deck::Suit) -> f32 {
+        self.resolve(stat, Some(suit))
+    }
+
+    pub fn move_speed(&self) -> f32 {
+        self.get(Stat::MoveSpeed).max(0.0)
+    }
+
+    pub fn max_health(&self) -> f32 {
+        self.get(Stat::MaxHealth)
+    }
+
+    pub fn fire_rate(&self) -> f32 {
+        self.get(Stat::FireRate)
+    }
+
+    pub fn damage(&self) -> f32 {
+        self.get(Stat::Damage)
+    }
+
+    pub fn crit_chance(&self, suit: deck::Suit) -> f32 {
+        self.get_for(Stat::CritChance, suit).clamp(0.0, 1.0)
+    }
+
+    pub fn pickup_radius(&self) -> f32 {
+        self.get(Stat::PickupRadius)
+            .min(consts::PICKUP_MAGNET_MAX_RADIUS)
+    }
+
+    pub fn armor(&self) -> f32 {
+        self.get(Stat::Armor).min(consts::PLAYER_MAX_ARMOR)
+    }
+
+    pub fn regen(&self) -> f32 {
+        self.get(Stat::Regen)
+    }
+
+    pub fn pierce(&self, suit: deck::Suit) -> i32 {
+        self.get_for(Stat::Pierce, suit).round() as i32
+    }
+
+    pub fn heal_amount(&self, suit: deck::Suit) -> f32 {
+        if mq::rand::gen_range(0.0, 1.0) < self.get_for(Stat::HealChance, suit) {
+            consts::HEARTS_HEAL_AMOUNT
+        } else {
+            0.0
+        }
+    }
+
+    pub fn on_hit_status_effects(&self, suit: deck::Suit) -> Vec<status_effect::StatusEffect> {
+        let stat = |stat: Stat| self.get_for(stat, suit);
+        let mut status_effects = Vec::new();
+
+        if stat(Stat::StunTime) > 0.0 {
+            status_effects.push(status_effect::StatusEffect::Stun(stat(Stat::StunTime)));
+        }
+        if mq::rand::gen_range(0.0, 1.0) < stat(Stat::FreezeChance) {
+            status_effects.push(status_effect::StatusEffect::Freeze(
+                consts::CLUBS_FREEZE_TIME,
+            ));
+        }
+        if stat(Stat::SlowSpeed) < 1.0 {
+            status_effects.push(status_effect::StatusEffect::Slow(
+                consts::CLUBS_SLOW_TIME,
+                stat(Stat::SlowSpeed),
+            ));
+        }
+        if stat(Stat::BurnDamage) > 0.0 {
+            status_effects.push(status_effect::StatusEffect::Burn(
+                consts::DIAMONDS_BURN_TIME,
+                stat(Stat::BurnDamage),
+            ));
+        }
+        if stat(Stat::Vulnerable) > 0.0 {
+            status_effects.push(status_effect::StatusEffect::Vulnerable(
+                consts::HEARTS_VULNERABLE_TIME,
+                stat(Stat::Vulnerable),
+            ));
+        }
+        let poison_stacks = stat(Stat::PoisonStacks).round() as u32;
+        if poison_stacks > 0 {
+            status_effects.push(status_effect::StatusEffect::Poison(
+                consts::SPADES_POISON_TIME,
+                poison_stacks,
+            ));
+        }
+
+        status_effects
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::assert_close;
+
+    #[test]
+    fn starts_from_the_base_values() {
+        let stats = Stats::new();
+        assert_close(stats.move_speed(), consts::PLAYER_SPEED);
+        assert_close(stats.fire_rate(), 1.0);
+        assert_close(stats.get(Stat::Pierce), 0.0);
+        assert_close(stats.get(Stat::SlowSpeed), 1.0);
+    }
+
+    #[test]
+    fn additions_come_before_multipliers() {
+        let mut stats = Stats::new();
+        stats.add_modifier(
+            Source::DeckEdit,
+            Scope::All,
+            Modifier::Mul(Stat::Damage, 2.0),
+        );
+        stats.add_modifier(
+            Source::DeckEdit,
+            Scope::All,
+            Modifier::Add(Stat::Damage, 0.5),
+        );
+        stats.add_modifier(
+            Source::Powerup,
+            Scope::All,
+            Modifier::Mul(Stat::Damage, 1.5),
+        );
+        assert_close(stats.get(Stat::Damage), (1.0 + 0.5) * 2.0 * 1.5);
+        // other stats are untouched
+        assert_close(stats.fire_rate(), 1.0);
+    }
+
+    #[test]
+    fn suit_scoped_modifiers() {
+        let mut stats = Stats::new();
+        stats.add_modifier(
+            Source::Powerup,
+            Scope::Suit(deck::Suit::Diamonds),
+            Modifier::Add(Stat::Pierce, 2.0),
+        );
+        stats.add_modifier(
+            Source::Powerup,
+            Scope::All,
+            Modifier::Add(Stat::Pierce, 1.0),
+        );
+
+        assert_eq!(stats.pierce(deck::Suit::Diamonds), 3);
+        assert_eq!(stats.pierce(deck::Suit::Spades), 1);
+        // only counts for shots from that suit
+        assert_close(stats.get(Stat::Pierce), 1.0);
+    }
+
+    #[test]
+    fn set_modifiers_replaces_only_its_source() {
+        let mut stats = Stats::new();
+        stats.add_modifier(
+            Source::Powerup,
+            Scope::All,
+            Modifier::Mul(Stat::FireRate, 2.0),
+        );
+        stats.set_modifiers(Source::Hand, vec![Modifier::Mul(Stat::FireRate, 1.5)]);
+        assert_close(stats.fire_rate(), 3.0);
+
+        stats.set_modifiers(Source::Hand, vec![Modifier::Mul(Stat::FireRate, 1.25)]);
+        assert_close(stats.fire_rate(), 2.5);
+
+        stats.set_modifiers(Source::Hand, Vec::new());
+        assert_close(stats.fire_rate(), 2.0);
+    }
+
+    #[test]
+    fn accessors_clamp() {
+        let mut stats = Stats::new();
+        stats.add_modifier(
+            Source::Powerup,
+            Scope::All,
+            Modifier::Add(Stat::CritChance, 5.0),
+        );
+        stats.add_modifier(
+            Source::Powerup,
+            Scope::All,
+            Modifier::Add(Stat::PickupRadius, 100.0),
+        );
+        stats.add_modifier(Source::Powerup, Scope::All, Modifier::Add(Stat::Armor, 5.0));
+        stats.set_modifiers(
+            Source::StatusEffect,
+            vec![Modifier::Add(Stat::MoveSpeed, -100.0)],
+        );
+
+        assert_close(stats.crit_chance(deck::Suit::Spades), 1.0);
+        assert_close(stats.pickup_radius(), consts::PICKUP_MAGNET_MAX_RADIUS);
+        assert_close(stats.armor(), consts::PLAYER_MAX_ARMOR);
+        assert_close(stats.move_speed(), 0.0);
+    }
+
+    #[test]
+    fn on_hit_status_effects() {
+        let mut stats = Stats::new();
+        assert!(stats.on_hit_status_effects(deck::Suit::Clubs).is_empty());
+
+        let clubs = Scope::Suit(deck::Suit::Clubs);
+        stats.add_modifier(Source::Powerup, clubs, Modifier::Add(Stat::StunTime, 0.5));
+        stats.add_modifier(
+            Source::Powerup,
+            clubs,
+            Modifier::Add(Stat::FreezeChance, 1.0),
+        );
+        stats.add_modifier(Source::Powerup, clubs, Modifier::Mul(Stat::SlowSpeed, 0.8));
+
+        let status_effects = stats.on_hit_status_effects(deck::Suit::Clubs);
+        assert_eq!(status_effects.len(), 3);
+        assert!(
+            matches!(status_effects[0], status_effect::StatusEffect::Stun(time) if time == 0.5)
+        );
+        assert!(matches!(
+            status_effects[1],
+            status_effect::StatusEffect::Freeze(_)
+        ));
+        assert!(matches!(
+            status_effects[2],
+            status_effect::StatusEffect::Slow(_, speed) if speed == 0.8
+        ));
+
+        assert!(stats.on_hit_status_effects(deck::Suit::Hearts).is_empty());
+    }
+}
@@ -0,0 +1,328 @@
+use macroquad::prelude as mq;
+
+use crate::{colors, consts, stats};
+
+#[derive(Clone, Copy)]
+pub enum StatusEffect {
+    Stun(f32),            // duration
+    Burn(f32, f32),       // duration, damage per second
+    Slow(f32, f32),       // duration, speed modifier
+    Freeze(f32),          // duration
+    Poison(f32, u32),     // duration, stacks
+    Vulnerable(f32, f32), // duration, extra damage taken (0.25 = +25%)
+    Knockback(mq::Vec2),  // velocity in tiles per second
+}
+
+// how long a single effect lasts and how strong it is
+#[derive(Clone, Copy)]
+struct Timed<T> {
+    time_remaining: f32,
+    value: T,
+}
+
+impl<T: Copy> Timed<T> {
+    fn new(value: T) -> Self {
+        Self {
+            time_remaining: 0.0,
+            value,
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.time_remaining > 0.0
+    }
+
+    fn update(&mut self, delta: f32) {
+        self.time_remaining = (self.time_remaining - delta).max(0.0);
+    }
+}
+
+pub struct StatusEffects {
+    stun: Timed<()>,
+    burn: Timed<f32>,
+    slow: Timed<f32>,
+    freeze: Timed<()>,
+    poison: Timed<u32>,
+    vulnerable: Timed<f32>,
+    knockback: mq::Vec2,
+}
+
+impl StatusEffects {
+    pub fn new() -> Self {
+        Self {
+            stun: Timed::new(()),
+            burn: Timed::new(0.0),
+            slow: Timed::new(1.0),
+            freeze: Timed::new(()),
+            poison: Timed::new(0),
+            vulnerable: Timed::new(0.0),
+            knockback: mq::Vec2::ZERO,
+        }
+    }
+
+    pub fn apply(&mut self, status_effect: StatusEffect) {
+        match status_effect {
+            // stun time stacks
+            StatusEffect::Stun(time) => self.stun.time_remaining += time,
+            // the strongest burn/slow/vulnerability wins, durations refresh
+            StatusEffect::Burn(time, damage) => {
+                if !self.burn.is_active() || damage > self.burn.value {
+                    self.burn.value = damage;
+                }
+                self.burn.time_remaining = self.burn.time_remaining.max(time);
+            }
+            StatusEffect::Slow(time, speed_mod) => {
+                if !self.slow.is_active() || speed_mod < self.slow.value {
+                    self.slow.value = speed_mod;
+                }
+                self.slow.time_remaining = self.slow.time_remaining.max(time);
+            }
+            StatusEffect::Vulnerable(time, extra_damage) => {
+                if !self.vulnerable.is_active() || extra_damage > self.vulnerable.value {
+                    self.vulnerable.value = extra_damage;
+                }
+                self.vulnerable.time_remaining = self.vulnerable.time_remaining.max(time);
+            }
+            // freezing doesn't stack, only refreshes
+            StatusEffect::Freeze(time) => {
+                self.freeze.time_remaining = self.freeze.time_remaining.max(time)
+            }
+            // poison stacks add up (to a max) and the duration refreshes
+            StatusEffect::Poison(time, stacks) => {
+                if !self.poison.is_active() {
+                    self.poison.value = 0;
+                }
+                self.poison.value =
+                    (self.poison.value + stacks).min(consts::STATUS_POISON_MAX_STACKS);
+                self.poison.time_remaining = self.poison.time_remaining.max(time);
+            }
+            StatusEffect::Knockback(velocity) => self.knockback += velocity,
+        }
+    }
+
+    // returns the damage over time taken this frame
+    pub fn update(&mut self, delta: f32) -> f32 {
+        let mut damage = 0.0;
+        if self.burn.is_active() {
+            damage += self.burn.value * delta.min(self.burn.time_remaining);
+        }
+        if self.poison.is_active() {
+            damage += self.poison.value as f32
+                * consts::STATUS_POISON_DAMAGE_PER_STACK
+                * delta.min(self.poison.time_remaining);
+        }
+
+        self.stun.update(delta);
+        self.burn.update(delta);
+        self.slow.update(delta);
+        self.freeze.update(delta);
+        self.poison.update(delta);
+        self.vulnerable.update(delta);
+
+        damage * self.damage_taken_mod()
+    }
+
+    // movement from knockback this frame, decays over time
+    pub fn knockback_movement(&mut self, delta: f32) -> mq::Vec2 {
+        let movement = self.knockback * delta;
+        self.knockback *= (-consts::STATUS_KNOCKBACK_DECAY * delta).exp();
+        if self.knockback.length() < consts::STATUS_KNOCKBACK_MIN_SPEED {
+            self.knockback = mq::Vec2::ZERO;
+        }
+        movement
+    }
+
+    pub fn is_stunned(&self) -> bool {
+        self.stun.is_active() || self.freeze.is_active()
+    }
+
+    pub fn speed_mod(&self) -> f32 {
+        if self.slow.is_active() {
+            self.slow.value
+        } else {
+            1.0
+        }
+    }
+
+    // how the effects change the stats of whoever has them
+    pub fn stat_modifiers(&self) -> Vec<stats::Modifier> {
+        let speed_mod = if self.is_stunned() {
+            0.0
+        } else {
+            self.speed_mod()
+        };
+        vec![
+            stats::Modifier::Mul(stats::Stat::MoveSpeed, speed_mod),
+            stats::Modifier::Add(stats::Stat::Armor, 1.0 - self.damage_taken_mod()),
+        ]
+    }
+
+    pub fn damage_taken_mod(&self) -> f32 {
+        if self.vulnerable.is_active() {
+            1.0 + self.vulnerable.value
+        } else {
+            1.0
+        }
+    }
+
+    pub fn draw(&self, draw_pos: mq::Vec2, square_radius: f32, scale: f32) {
+        let x = draw_pos.x - square_radius / 2.0;
+        let y = draw_pos.y - square_radius / 2.0;
+
+        if self.freeze.is_active() {
+            mq::draw_rectangle(x, y, square_radius, square_radius, colors::NORD8_BIG_ALPHA);
+        }
+        if self.is_stunned() {
+            mq::draw_rectangle_lines(
+                x,
+                y,
+                square_radius,
+                square_radius,
+                consts::ENEMY_STUNNED_THICKNESS * scale,
+                if self.freeze.is_active() {
+                    colors::NORD8
+                } else {
+                    colors::NORD10
+                },
+            );
+        }
+
+        // one pip per lasting effect under the enemy
+        let pips = [
+            (self.burn.is_active(), colors::NORD13),
+            (self.slow.is_active(), colors::NORD9),
+            (self.poison.is_active(), colors::NORD14),
+            (self.vulnerable.is_active(), colors::NORD11),
+        ];
+        let pip_size = consts::STATUS_PIP_SIZE * scale;
+        let mut pip_x = x;
+        for (_, color) in pips.iter().filter(|(active, _)| *active) {
+            mq::draw_rectangle(pip_x, y + square_radius, pip_size, pip_size, *color);
+            pip_x += pip_size * 1.5;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::assert_close;
+
+    #[test]
+    fn effects_wear_off() {
+        let mut status_effects = StatusEffects::new();
+        status_effects.apply(StatusEffect::Stun(1.0));
+        status_effects.apply(StatusEffect::Slow(2.0, 0.5));
+        assert!(status_effects.is_stunned());
+        assert_close(status_effects.speed_mod(), 0.5);
+
+        status_effects.update(1.5);
+        assert!(!status_effects.is_stunned());
+        assert_close(status_effects.speed_mod(), 0.5);
+
+        status_effects.update(1.0);
+        assert_close(status_effects.speed_mod(), 1.0);
+    }
+
+    #[test]
+    fn stun_time_stacks_but_freeze_only_refreshes() {
+        let mut status_effects = StatusEffects::new();
+        status_effects.apply(StatusEffect::Stun(1.0));
+        status_effects.apply(StatusEffect::Stun(1.0));
+        status_effects.update(1.5);
+        assert!(status_effects.is_stunned());
+
+        let mut status_effects = StatusEffects::new();
+        status_effects.apply(StatusEffect::Freeze(1.0));
+        status_effects.apply(StatusEffect::Freeze(1.0));
+        status_effects.update(1.5);
+        assert!(!status_effects.is_stunned());
+    }
+
+    #[test]
+    fn strongest_effect_wins() {
+        let mut status_effects = StatusEffects::new();
+        status_effects.apply(StatusEffect::Slow(1.0, 0.5));
+        status_effects.apply(StatusEffect::Slow(3.0, 0.8));
+        assert_close(status_effects.speed_mod(), 0.5);
+
+        // the longer duration is kept
+        status_effects.update(2.0);
+        assert!(status_effects.speed_mod() < 1.0);
+
+        status_effects.apply(StatusEffect::Vulnerable(1.0, 0.5));
+        status_effects.apply(StatusEffect::Vulnerable(1.0, 0.25));
+        assert_close(status_effects.damage_taken_mod(), 1.5);
+    }
+
+    #[test]
+    fn damage_over_time() {
+        let mut status_effects = StatusEffects::new();
+        status_effects.apply(StatusEffect::Burn(1.0, 2.0));
+        assert_close(status_effects.update(0.5), 1.0);
+        // only the time left counts
+        assert_close(status_effects.update(1.0), 1.0);
+        assert_close(status_effects.update(1.0), 0.0);
+
+        status_effects.apply(StatusEffect::Poison(1.0, 2));
+        status_effects.apply(StatusEffect::Poison(1.0, 2));
+        assert_close(
+            status_effects.update(1.0),
+            4.0 * consts::STATUS_POISON_DAMAGE_PER_STACK,
+        );
+
+        // vulnerability increases damage over time too
+        status_effects.apply(StatusEffect::Vulnerable(2.0, 0.5));
+        status_effects.apply(StatusEffect::Burn(1.0, 2.0));
+        assert_close(status_effects.update(1.0), 3.0);
+    }
+
+    #[test]
+    fn poison_stacks_are_capped_and_reset() {
+        let mut status_effects = StatusEffects::new();
+        status_effects.apply(StatusEffect::Poison(1.0, consts::STATUS_POISON_MAX_STACKS));
+        status_effects.apply(StatusEffect::Poison(1.0, 5));
+        assert_close(
+            status_effects.update(1.0),
+            consts::STATUS_POISON_MAX_STACKS as f32 * consts::STATUS_POISON_DAMAGE_PER_STACK,
+        );
+
+        // worn off, so the stacks start over
+        status_effects.apply(StatusEffect::Poison(1.0, 1));
+        assert_close(
+            status_effects.update(1.0),
+            consts::STATUS_POISON_DAMAGE_PER_STACK,
+        );
+    }
+
+    #[test]
+    fn knockback_decays() {
+        let mut status_effects = StatusEffects::new();
+        status_effects.apply(StatusEffect::Knockback(mq::Vec2::new(2.0, 0.0)));
+
+        let first = status_effects.knockback_movement(0.1);
+        assert_close(first.x, 0.2);
+        let second = status_effects.knockback_movement(0.1);
+        assert!(second.x > 0.0 && second.x < first.x);
+
+        for _ in 0..100 {
+            status_effects.knockback_movement(0.1);
+        }
+        assert_eq!(status_effects.knockback_movement(0.1), mq::Vec2::ZERO);
+    }
+
+    #[test]
+    fn stat_modifiers() {
+        let mut stats = stats::Stats::new();
+        let mut status_effects = StatusEffects::new();
+        status_effects.apply(StatusEffect::Slow(1.0, 0.5));
+        status_effects.apply(StatusEffect::Vulnerable(1.0, 0.25));
+        stats.set_modifiers(stats::Source::StatusEffect, status_effects.stat_modifiers());
+        assert_close(stats.move_speed(), consts::PLAYER_SPEED * 0.5);
+        assert_close(stats.armor(), consts::PLAYER_ARMOR - 0.25);
+
+        status_effects.apply(StatusEffect::Stun(1.0));
+        stats.set_modifiers(stats::Source::StatusEffect, status_effects.stat_modifiers());
+        assert_close(stats.move_speed(), 0.0);
+    }
+}
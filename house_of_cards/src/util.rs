@@ -27,3 +27,9 @@ pub fn clicked_on(
 pub struct Shot(pub bool);
 pub struct Moved(pub bool);
 pub struct Ticked(pub bool);
+
+// float comparison for tests
+#[cfg(test)]
+pub fn assert_close(a: f32, b: f32) {
+    assert!((a - b).abs() < 1e-5, "{} != {}", a, b);
+}
@@ -0,0 +1,427 @@
+use macroquad::prelude as mq;
+
+use crate::{consts, enemy, json::Json};
+
+// how a wave stat grows, matches the shape of the formulas in consts
+#[derive(Clone, Copy)]
+enum Curve {
+    Linear(f32, f32),   // value at wave 1, change per wave
+    Log(f32, f32, f32), // value at wave 0, value at the end wave, end wave
+}
+
+impl Curve {
+    fn at(&self, wave: i32) -> f32 {
+        match *self {
+            Curve::Linear(base, per_wave) => base + per_wave * (wave - 1) as f32,
+            Curve::Log(start, end, end_wave) => consts::AUTO_LOG(wave as f32, start, end, end_wave),
+        }
+    }
+
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let field = |key: &str| {
+            json.get(key)
+                .and_then(Json::as_f32)
+                .ok_or(format!("curve is missing '{}'", key))
+        };
+        if json.get("per_wave").is_some() {
+            Ok(Curve::Linear(field("base")?, field("per_wave")?))
+        } else {
+            let end_wave = field("end_wave")?;
+            if end_wave <= 0.0 {
+                return Err("curve 'end_wave' must be above 0".to_owned());
+            }
+            Ok(Curve::Log(field("start")?, field("end")?, end_wave))
+        }
+    }
+}
+
+// an optional number that has to be in range
+fn number_in_range(
+    json: &Json,
+    key: &str,
+    in_range: fn(f32) -> bool,
+    range: &str,
+) -> Result<Option<f32>, String> {
+    match json.get(key) {
+        Some(value) => match value.as_f32() {
+            Some(number) if in_range(number) => Ok(Some(number)),
+            _ => Err(format!("'{}' must be a number {}", key, range)),
+        },
+        None => Ok(None),
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum WaveSpawn {
+    Enemy(enemy::EnemyType),
+    Formation(enemy::Formation),
+}
+
+impl WaveSpawn {
+    fn from_name(name: &str) -> Result<Self, String> {
+        enemy::EnemyType::from_name(name)
+            .map(WaveSpawn::Enemy)
+            .or_else(|| enemy::Formation::from_name(name).map(WaveSpawn::Formation))
+            .ok_or(format!("unknown spawn '{}'", name))
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct WaveEvent {
+    pub time: f32, // seconds into the wave
+    pub spawn: WaveSpawn,
+}
+
+// settings for a wave and every wave after it until the next definition
+#[derive(Clone)]
+struct WaveDefinition {
+    first_wave: i32,
+    count: Option<i32>,
+    hp: Option<f32>,
+    speed: Option<f32>,                        // tiles per second
+    spawn_rate: Option<f32>,                   // enemies per second
+    mix: Option<Vec<(enemy::EnemyType, f32)>>, // (type, weight)
+    formations: Option<Vec<enemy::Formation>>,
+    formation_chance: Option<f32>,
+    events: Option<Vec<WaveEvent>>,
+    super_enemy: Option<bool>, // spawn a super enemy halfway through
+}
+
+impl WaveDefinition {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let first_wave = json
+            .get("wave")
+            .and_then(Json::as_i32)
+            .ok_or("wave definition is missing 'wave'")?;
+        if first_wave < 1 {
+            return Err("'wave' must be at least 1".to_owned());
+        }
+        let positive = |key: &str| number_in_range(json, key, |n| n > 0.0, "above 0");
+
+        let mix = match json.get("mix").map(|mix| mix.as_object()) {
+            Some(Some(entries)) => Some(
+                entries
+                    .iter()
+                    .map(|(name, weight)| {
+                        let enemy_type = enemy::EnemyType::from_name(name)
+                            .ok_or(format!("unknown enemy '{}'", name))?;
+                        let weight = weight
+                            .as_f32()
+                            .filter(|weight| *weight >= 0.0)
+                            .ok_or("mix weights must be numbers of at least 0")?;
+                        Ok((enemy_type, weight))
+                    })
+                    .collect::<Result<Vec<_>, String>>()?,
+            ),
+            Some(None) => return Err("'mix' must be an object".to_owned()),
+            None => None,
+        };
+        if mix
+            .as_ref()
+            .is_some_and(|mix| mix.iter().map(|(_, weight)| weight).sum::<f32>() <= 0.0)
+        {
+            return Err("'mix' needs a weight above 0".to_owned());
+        }
+
+        let formations = match json
+            .get("formations")
+            .map(|formations| formations.as_array())
+        {
+            Some(Some(names)) => Some(
+                names
+                    .iter()
+                    .map(|name| {
+                        name.as_str()
+                            .and_then(enemy::Formation::from_name)
+                            .ok_or("unknown formation".to_owned())
+                    })
+                    .collect::<Result<Vec<_>, String>>()?,
+            ),
+            Some(None) => return Err("'formations' must be an array".to_owned()),
+            None => None,
+        };
+
+        let events = match json.get("events").map(|events| events.as_array()) {
+            Some(Some(events)) => Some(
+                events
+                    .iter()
+                    .map(|event| {
+                        let time = event
+                            .get("time")
+                            .and_then(Json::as_f32)
+                            .ok_or("event is missing 'time'")?;
+                        if time < 0.0 {
+                            return Err("event 'time' must be at least 0".to_owned());
+                        }
+                        let spawn = event
+                            .get("spawn")
+                            .and_then(Json::as_str)
+                            .ok_or("event is missing 'spawn'")?;
+                        Ok(WaveEvent {
+                            time,
+                            spawn: WaveSpawn::from_name(spawn)?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, String>>()?,
+            ),
+            Some(None) => return Err("'events' must be an array".to_owned()),
+            None => None,
+        };
+
+        Ok(Self {
+            first_wave,
+            count: number_in_range(json, "count", |n| n >= 1.0, "of at least 1")?
+                .map(|count| count.round() as i32),
+            hp: positive("hp")?,
+            speed: number_in_range(json, "speed", |n| n >= 0.0, "of at least 0")?,
+            spawn_rate: positive("spawn_rate")?,
+            mix,
+            formations,
+            formation_chance: number_in_range(
+                json,
+                "formation_chance",
+                |n| (0.0..=1.0).contains(&n),
+                "from 0 to 1",
+            )?,
+            events,
+            super_enemy: json.get("super").and_then(Json::as_bool),
+        })
+    }
+}
+
+// wave settings from a file, anything missing falls back to the formulas in consts
+#[derive(Clone)]
+pub struct WaveConfig {
+    count: Option<Curve>,
+    hp: Option<Curve>,
+    speed: Option<Curve>,
+    spawn_rate: Option<Curve>,
+    super_wave_start: Option<i32>,
+    max_enemies: Option<usize>,
+    waves: Vec<WaveDefinition>, // sorted by first wave
+}
+
+impl WaveConfig {
+    pub fn new() -> Self {
+        Self {
+            count: None,
+            hp: None,
+            speed: None,
+            spawn_rate: None,
+            super_wave_start: None,
+            max_enemies: None,
+            waves: Vec::new(),
+        }
+    }
+
+    pub async fn load() -> Self {
+        // a missing file is fine, a broken one is worth a warning
+        let Ok(text) = mq::load_string(consts::WAVES_PATH).await else {
+            return WaveConfig::new();
+        };
+        match WaveConfig::parse(&text) {
+            Ok(wave_config) => wave_config,
+            Err(error) => {
+                mq::warn!("ignoring {}: {}", consts::WAVES_PATH, error);
+                WaveConfig::new()
+            }
+        }
+    }
+
+    fn parse(text: &str) -> Result<Self, String> {
+        let json = Json::parse(text)?;
+
+        let scaling = json.get("scaling");
+        let curve = |key: &str| {
+            scaling
+                .and_then(|scaling| scaling.get(key))
+                .map(Curve::from_json)
+                .transpose()
+        };
+
+        let mut waves = match json.get("waves") {
+            Some(waves) => waves
+                .as_array()
+                .ok_or("'waves' must be an array")?
+                .iter()
+                .map(WaveDefinition::from_json)
+                .collect::<Result<Vec<_>, String>>()?,
+            None => Vec::new(),
+        };
+        waves.sort_by_key(|definition| definition.first_wave);
+
+        Ok(Self {
+            count: curve("count")?,
+            hp: curve("hp")?,
+            speed: curve("speed")?,
+            spawn_rate: curve("spawn_rate")?,
+            super_wave_start: json.get("super_wave_start").and_then(Json::as_i32),
+            max_enemies: number_in_range(&json, "max_enemies", |n| n >= 1.0, "of at least 1")?
+                .map(|max_enemies| max_enemies.round() as usize),
+            waves,
+        })
+    }
+
+    // definitions don't build on each other, anything a definition leaves out uses the curves
+    fn definition(&self, wave: i32) -> Option<&WaveDefinition> {
+        self.waves
+            .iter()
+            .rev()
+            .find(|definition| definition.first_wave <= wave)
+    }
+
+    pub fn count(&self, wave: i32) -> i32 {
+        self.definition(wave)
+            .and_then(|definition| definition.count)
+            .or(self
+                .count
+                .map(|curve| (curve.at(wave).round() as i32).max(1)))
+            .unwrap_or_else(|| consts::ENEMY_WAVE_COUNT(wave))
+    }
+
+    pub fn hp(&self, wave: i32) -> f32 {
+        self.definition(wave)
+            .and_then(|definition| definition.hp)
+            .or(self.hp.map(|curve| curve.at(wave).max(consts::WAVE_MIN_HP)))
+            .unwrap_or_else(|| consts::ENEMY_WAVE_HP(wave))
+    }
+
+    pub fn speed(&self, wave: i32) -> f32 {
+        self.definition(wave)
+            .and_then(|definition| definition.speed)
+            .or(self.speed.map(|curve| curve.at(wave).max(0.0)))
+            .unwrap_or_else(|| consts::ENEMY_WAVE_SPEED(wave))
+    }
+
+    pub fn spawn_rate(&self, wave: i32) -> f32 {
+        self.definition(wave)
+            .and_then(|definition| definition.spawn_rate)
+            .or(self
+                .spawn_rate
+                .map(|curve| curve.at(wave).max(consts::WAVE_MIN_SPAWN_RATE)))
+            .unwrap_or_else(|| consts::ENEMY_WAVE_SPAWN_RATE(wave))
+    }
+
+    fn super_wave_start(&self) -> i32 {
+        self.super_wave_start
+            .unwrap_or(consts::ENEMY_SUPER_WAVE_START)
+    }
+
+    pub fn max_enemies(&self) -> usize {
+        self.max_enemies.unwrap_or(consts::ENEMY_MAX_LIVE)
+    }
+
+    pub fn formation_chance(&self, wave: i32) -> f32 {
+        self.definition(wave)
+            .and_then(|definition| definition.formation_chance)
+            .unwrap_or(consts::ENEMY_FORMATION_CHANCE)
+    }
+
+    // None means use the default enemy chances
+    pub fn mix(&self, wave: i32) -> Option<&[(enemy::EnemyType, f32)]> {
+        self.definition(wave)
+            .and_then(|definition| definition.mix.as_deref())
+    }
+
+    // None means use the default formations for the wave
+    pub fn formations(&self, wave: i32) -> Option<&[enemy::Formation]> {
+        self.definition(wave)
+            .and_then(|definition| definition.formations.as_deref())
+    }
+
+    pub fn events(&self, wave: i32) -> Option<&[WaveEvent]> {
+        self.definition(wave)
+            .and_then(|definition| definition.events.as_deref())
+    }
+
+    // by default timed events replace the halfway super enemy
+    pub fn spawns_super(&self, wave: i32) -> bool {
+        if wave <= self.super_wave_start() {
+            return false;
+        }
+        match self.definition(wave) {
+            Some(definition) => definition
+                .super_enemy
+                .unwrap_or(definition.events.is_none()),
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_example_file() {
+        let wave_config =
+            WaveConfig::parse(include_str!("../resources/waves.example.json")).unwrap();
+
+        assert_eq!(wave_config.count(1), 5);
+        assert_eq!(wave_config.count(7), 25);
+        // wave 8 doesn't carry over wave 7's count
+        assert_eq!(wave_config.count(8), 26);
+        assert_eq!(wave_config.hp(2), 18.0);
+        assert_eq!(wave_config.max_enemies(), consts::ENEMY_MAX_LIVE);
+
+        let mix = wave_config.mix(2).unwrap();
+        assert_eq!(mix.len(), 2);
+        assert!(mix[0].0 == enemy::EnemyType::Melee && mix[0].1 == 2.0);
+        assert_eq!(wave_config.formations(1).map(<[_]>::len), Some(0));
+        assert_eq!(wave_config.formation_chance(3), 0.25);
+
+        assert_eq!(wave_config.events(7).map(<[_]>::len), Some(3));
+        assert!(wave_config.events(3).is_none());
+
+        // queens spawn after wave 2, except where timed events replace them
+        assert!(!wave_config.spawns_super(2));
+        assert!(wave_config.spawns_super(3));
+        assert!(!wave_config.spawns_super(7));
+    }
+
+    #[test]
+    fn an_empty_file_uses_the_defaults() {
+        let wave_config = WaveConfig::parse("{}").unwrap();
+        for wave in 1..20 {
+            assert_eq!(wave_config.count(wave), consts::ENEMY_WAVE_COUNT(wave));
+            assert_eq!(wave_config.hp(wave), consts::ENEMY_WAVE_HP(wave));
+            assert_eq!(
+                wave_config.spawn_rate(wave),
+                consts::ENEMY_WAVE_SPAWN_RATE(wave)
+            );
+        }
+    }
+
+    #[test]
+    fn curves_stay_in_range() {
+        let wave_config = WaveConfig::parse(
+            r#"{ "scaling": {
+                "count": { "base": 5, "per_wave": -1 },
+                "spawn_rate": { "base": 1, "per_wave": -1 }
+            } }"#,
+        )
+        .unwrap();
+        assert_eq!(wave_config.count(20), 1);
+        assert_eq!(wave_config.spawn_rate(20), consts::WAVE_MIN_SPAWN_RATE);
+    }
+
+    #[test]
+    fn rejects_out_of_range_values() {
+        let invalid = [
+            r#"{ "waves": [{ "wave": 1, "spawn_rate": 0 }] }"#,
+            r#"{ "waves": [{ "wave": 1, "hp": -5 }] }"#,
+            r#"{ "waves": [{ "wave": 1, "count": -2 }] }"#,
+            r#"{ "waves": [{ "wave": 0 }] }"#,
+            r#"{ "waves": [{ "wave": 1, "mix": { "pawn": -1, "bishop": 2 } }] }"#,
+            r#"{ "waves": [{ "wave": 1, "mix": { "pawn": 0 } }] }"#,
+            r#"{ "waves": [{ "wave": 1, "formation_chance": 2 }] }"#,
+            r#"{ "waves": [{ "wave": 1, "events": [{ "time": -1, "spawn": "ring" }] }] }"#,
+            r#"{ "waves": [{ "wave": 1, "mix": { "dragon": 1 } }] }"#,
+            r#"{ "max_enemies": 0 }"#,
+            r#"{ "scaling": { "hp": { "start": 1, "end": 5, "end_wave": 0 } } }"#,
+        ];
+        for text in invalid {
+            assert!(WaveConfig::parse(text).is_err(), "accepted {}", text);
+        }
+    }
+}
@@ -1,5 +1,55 @@
 use crate::util;
 
+#[derive(Clone, Copy)]
+pub enum BulletPattern {
+    Single,
+    Spread(u32, f32, f32), // (bullet count, total angle in radians, damage per bullet)
+    Homing(f32),           // turn rate in radians per second
+    Wide(f32, i32),        // (size modifier, extra pierce)
+}
+
+impl BulletPattern {
+    pub fn directions(&self, direction: f32) -> Vec<f32> {
+        match *self {
+            BulletPattern::Spread(count, angle, _) if count > 1 => {
+                let step = angle / (count - 1) as f32;
+                (0..count)
+                    .map(|i| direction - angle / 2.0 + step * i as f32)
+                    .collect()
+            }
+            _ => vec![direction],
+        }
+    }
+
+    pub fn size_mod(&self) -> f32 {
+        match self {
+            BulletPattern::Wide(size_mod, _) => *size_mod,
+            _ => 1.0,
+        }
+    }
+
+    pub fn damage_mod(&self) -> f32 {
+        match self {
+            BulletPattern::Spread(_, _, damage_mod) => *damage_mod,
+            _ => 1.0,
+        }
+    }
+
+    pub fn extra_pierce(&self) -> i32 {
+        match self {
+            BulletPattern::Wide(_, pierce) => *pierce,
+            _ => 0,
+        }
+    }
+
+    pub fn homing(&self) -> Option<f32> {
+        match self {
+            BulletPattern::Homing(turn_rate) => Some(*turn_rate),
+            _ => None,
+        }
+    }
+}
+
 pub struct Weapon {
     pub fire_rate: f32, // shots per second
     pub range: f32,     // tiles
@@ -8,6 +58,7 @@ pub struct Weapon {
 
     pub time_until_next_shot: f32, // seconds
     pub bullet_speed: f32,         // tiles per second
+    pub pattern: BulletPattern,
 }
 
 impl Weapon {
@@ -18,6 +69,7 @@ impl Weapon {
         ms_modifier_walking: f32,
         ms_modifier_shooting: f32,
         bullet_speed: f32,
+        pattern: BulletPattern,
     ) -> Self {
         Self {
             fire_rate,
@@ -26,6 +78,7 @@ impl Weapon {
             ms_modifier_shooting,
             time_until_next_shot: 0.0,
             bullet_speed,
+            pattern,
         }
     }
 